@@ -0,0 +1,373 @@
+//! Headless command line access to the journal database.
+//!
+//! Shares the database file and the command logic with the desktop app, so
+//! it can be used from a terminal or from cron while the app is closed.
+
+use chrono::{Duration, Local, NaiveDate};
+use journal_lib::database::{
+    CreateTaskInput, Database, Date, Mood, Task, UpdateTaskInput, UpsertEntryInput, UpsertGoalInput,
+};
+use journal_lib::services::{dependencies, goals, interop, journal, scheduling, tasks};
+use std::env;
+use std::process::{self, Command};
+
+const USAGE: &str = "Usage: journal-cli <command> [args]
+
+Commands:
+  add-task <title> [--date D] [--end D] [--time HH:MM|all-day] [--zone TZ] [--duration MIN] [--priority P] [--remind MIN]
+  list [today|tomorrow|yesterday|YYYY-MM-DD] [END_DATE]
+  done <task-id>
+  write [DATE]                 Edit the day's journal entry in $EDITOR
+  goals [DATE]                 List the day's goals
+  goals set <n> <text> [--date D]
+  goals done <n> [--date D]
+  export [json|todo.txt] [START END]";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if let Err(e) = run(&args) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let Some((command, rest)) = args.split_first() else {
+        println!("{}", USAGE);
+        return Ok(());
+    };
+
+    if matches!(command.as_str(), "help" | "-h" | "--help") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let db = Database::new()?;
+
+    match command.as_str() {
+        "add-task" => add_task(&db, rest),
+        "list" => list(&db, rest),
+        "done" => done(&db, rest),
+        "write" => write(&db, rest),
+        "goals" => goals(&db, rest),
+        "export" => export(&db, rest),
+        other => Err(format!("unknown command '{}'\n\n{}", other, USAGE)),
+    }
+}
+
+fn add_task(db: &Database, args: &[String]) -> Result<(), String> {
+    let (positional, options) = split_options(args)?;
+    if positional.is_empty() {
+        return Err("add-task needs a title".to_string());
+    }
+
+    let mut input = CreateTaskInput {
+        title: positional.join(" "),
+        description: None,
+        scheduled_date: Date(Local::now().date_naive()),
+        scheduled_time: None,
+        duration_minutes: None,
+        priority: None,
+        reminder_minutes: None,
+        color: None,
+        end_date: None,
+        is_all_day: None,
+        time_zone: None,
+    };
+
+    for (name, value) in options {
+        match name.as_str() {
+            "date" => input.scheduled_date = Date(parse_day(&value)?),
+            "end" => input.end_date = Some(Date(parse_day(&value)?)),
+            "time" if value == "all-day" => input.is_all_day = Some(true),
+            "time" => input.scheduled_time = Some(value.parse()?),
+            "duration" => input.duration_minutes = Some(parse_number(&name, &value)?),
+            "zone" => input.time_zone = Some(value),
+            "priority" => input.priority = Some(value.parse()?),
+            "remind" => input.reminder_minutes = Some(parse_number(&name, &value)?),
+            other => return Err(format!("unknown option --{}", other)),
+        }
+    }
+
+    let task = tasks::create_task(db, input)?;
+    println!("{}", format_task(&task));
+    for conflict in scheduling::conflicts_for_task(db, &task)? {
+        eprintln!(
+            "warning: overlaps task {} from {} to {}",
+            short_id(&conflict.other_task_id),
+            conflict.overlap_start,
+            conflict.overlap_end
+        );
+    }
+    Ok(())
+}
+
+fn list(db: &Database, args: &[String]) -> Result<(), String> {
+    let start = match args.first() {
+        Some(day) => parse_day(day)?,
+        None => Local::now().date_naive(),
+    };
+    let end = match args.get(1) {
+        Some(day) => parse_day(day)?,
+        None => start,
+    };
+
+    let tasks = tasks::get_tasks_for_date_range(db, &format_date(start), &format_date(end))?;
+
+    let mut current_date = None;
+    for task in &tasks {
+        if start != end && current_date != Some(&task.occurrence_date) {
+            println!("{}", task.occurrence_date);
+            current_date = Some(&task.occurrence_date);
+        }
+        println!("{}", format_task(task));
+    }
+
+    Ok(())
+}
+
+fn done(db: &Database, args: &[String]) -> Result<(), String> {
+    let [prefix] = args else {
+        return Err("done needs exactly one task id".to_string());
+    };
+
+    let id = tasks::resolve_task_id(db, prefix)?;
+    let (task, synced) = tasks::update_task(
+        db,
+        UpdateTaskInput {
+            id,
+            title: None,
+            description: None,
+            scheduled_date: None,
+            scheduled_time: None,
+            duration_minutes: None,
+            priority: None,
+            is_completed: Some(true),
+            reminder_minutes: None,
+            color: None,
+            end_date: None,
+            is_all_day: None,
+            time_zone: None,
+            clear_scheduled_time: false,
+            clear_end_date: false,
+        },
+    )?;
+
+    println!("{}", format_task(&task));
+    for unblocked in dependencies::unblocked_dependents(db, &task.id)? {
+        println!("unblocked: {}", format_task(&unblocked));
+    }
+    for goal in synced {
+        println!("goal done: {}", goal.goal_text);
+    }
+    Ok(())
+}
+
+fn write(db: &Database, args: &[String]) -> Result<(), String> {
+    let date = match args.first() {
+        Some(day) => format_date(parse_day(day)?),
+        None => format_date(Local::now().date_naive()),
+    };
+
+    let existing = journal::get_entry_for_date(db, &date)?;
+    let original = existing
+        .as_ref()
+        .map(|e| e.content.clone())
+        .unwrap_or_default();
+    // Checked before editing so an entry with an unknown mood isn't edited
+    // only to fail on save
+    let mood: Option<Mood> = existing
+        .and_then(|e| e.mood)
+        .map(|mood| mood.parse())
+        .transpose()?;
+
+    let path = env::temp_dir().join(format!("journal-{}.md", date));
+    std::fs::write(&path, &original).map_err(|e| e.to_string())?;
+
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+    let mut editor_args = editor.split_whitespace();
+    let program = editor_args.next().ok_or("$EDITOR is empty")?;
+
+    let status = Command::new(program)
+        .args(editor_args)
+        .arg(&path)
+        .status()
+        .map_err(|e| format!("cannot start {}: {}", program, e))?;
+
+    let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    std::fs::remove_file(&path).ok();
+
+    if !status.success() {
+        return Err(format!(
+            "{} exited with {}, entry not saved",
+            program, status
+        ));
+    }
+    if content == original {
+        println!("No changes for {}", date);
+        return Ok(());
+    }
+
+    journal::upsert_entry(
+        db,
+        UpsertEntryInput {
+            entry_date: date.parse()?,
+            content,
+            mood,
+        },
+    )?;
+    println!("Saved entry for {}", date);
+    Ok(())
+}
+
+fn goals(db: &Database, args: &[String]) -> Result<(), String> {
+    let (positional, options) = split_options(args)?;
+
+    let mut date = Local::now().date_naive();
+    for (name, value) in options {
+        match name.as_str() {
+            "date" => date = parse_day(&value)?,
+            other => return Err(format!("unknown option --{}", other)),
+        }
+    }
+
+    match positional.split_first() {
+        Some((action, rest)) if action == "set" => {
+            let Some((order, text)) = rest.split_first().filter(|(_, text)| !text.is_empty())
+            else {
+                return Err("goals set needs a slot number and text".to_string());
+            };
+            let goal = goals::upsert_goal(
+                db,
+                UpsertGoalInput {
+                    goal_date: Date(date),
+                    goal_text: text.join(" "),
+                    goal_order: parse_number("slot", order)?,
+                    parent_goal_id: None,
+                },
+            )?;
+            println!("{}. {}", goal.goal_order, goal.goal_text);
+        }
+        Some((action, rest)) if action == "done" => {
+            let [order] = rest else {
+                return Err("goals done needs a slot number".to_string());
+            };
+            let order: i32 = parse_number("slot", order)?;
+            let goal = goals::get_goals_for_date(db, &format_date(date))?
+                .into_iter()
+                .find(|g| g.goal_order == order)
+                .ok_or_else(|| format!("No goal in slot {}", order))?;
+            if !goal.is_completed {
+                goals::toggle_goal_completion(db, &goal.id)?;
+            }
+        }
+        Some((day, [])) => {
+            date = parse_day(day)?;
+            print_goals(db, date)?;
+        }
+        Some(_) => return Err(format!("unknown goals command\n\n{}", USAGE)),
+        None => print_goals(db, date)?,
+    }
+
+    Ok(())
+}
+
+fn print_goals(db: &Database, date: NaiveDate) -> Result<(), String> {
+    for goal in goals::get_goals_for_date(db, &format_date(date))? {
+        let check = if goal.is_completed { "x" } else { " " };
+        println!("[{}] {}. {}", check, goal.goal_order, goal.goal_text);
+    }
+    Ok(())
+}
+
+fn export(db: &Database, args: &[String]) -> Result<(), String> {
+    let (format, range) = match args.split_first() {
+        Some((format, rest)) if format == "json" || format == "todo.txt" => (format.as_str(), rest),
+        _ => ("json", args),
+    };
+
+    let (start, end) = match range {
+        [] => (None, None),
+        [start, end] => (
+            Some(format_date(parse_day(start)?)),
+            Some(format_date(parse_day(end)?)),
+        ),
+        _ => return Err("export takes either no dates or a start and end date".to_string()),
+    };
+
+    let output = match format {
+        "todo.txt" => interop::export_todo_txt(db, start.as_deref(), end.as_deref())?,
+        _ => interop::export_json(db, start.as_deref(), end.as_deref())?,
+    };
+    println!("{}", output);
+    Ok(())
+}
+
+type Options = Vec<(String, String)>;
+
+/// Separates `--name value` pairs from positional arguments.
+fn split_options(args: &[String]) -> Result<(Vec<String>, Options), String> {
+    let mut positional = Vec::new();
+    let mut options = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.strip_prefix("--") {
+            Some(name) => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("--{} needs a value", name))?;
+                options.push((name.to_string(), value.clone()));
+            }
+            None => positional.push(arg.clone()),
+        }
+    }
+
+    Ok((positional, options))
+}
+
+fn parse_day(value: &str) -> Result<NaiveDate, String> {
+    let today = Local::now().date_naive();
+    match value {
+        "today" => Ok(today),
+        "tomorrow" => Ok(today + Duration::days(1)),
+        "yesterday" => Ok(today - Duration::days(1)),
+        _ => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map_err(|_| format!("invalid date '{}', expected YYYY-MM-DD", value)),
+    }
+}
+
+fn parse_number(name: &str, value: &str) -> Result<i32, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid {} '{}', expected a number", name, value))
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+fn format_task(task: &Task) -> String {
+    let check = if task.is_completed { "x" } else { " " };
+    let time = match task.scheduled_time.as_deref() {
+        _ if task.is_all_day => "all-d",
+        Some(time) => time,
+        None => "--:--",
+    };
+    format!(
+        "[{}] {}  {}  {:>4}m  {:<6}  {}",
+        check,
+        short_id(&task.id),
+        time,
+        task.duration_minutes,
+        task.priority,
+        task.title
+    )
+}
+
+fn short_id(id: &str) -> String {
+    id.chars().take(8).collect()
+}
@@ -0,0 +1,54 @@
+use super::operations::run_blocking;
+use crate::database::{Database, DependencyWarning, TaskDependencies};
+use crate::events::Change;
+use crate::services::dependencies;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn add_task_dependency(
+    app: AppHandle,
+    db: State<'_, Database>,
+    task_id: String,
+    depends_on_id: String,
+) -> Result<Vec<DependencyWarning>, String> {
+    run_blocking(&db, move |db| {
+        let warnings = dependencies::add_dependency(db, &task_id, &depends_on_id)?;
+        Change::DependencyAdded {
+            task_id,
+            depends_on_id,
+        }
+        .emit(&app);
+        Ok(warnings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn remove_task_dependency(
+    app: AppHandle,
+    db: State<'_, Database>,
+    task_id: String,
+    depends_on_id: String,
+) -> Result<(), String> {
+    run_blocking(&db, move |db| {
+        dependencies::remove_dependency(db, &task_id, &depends_on_id)?;
+        Change::DependencyRemoved {
+            task_id,
+            depends_on_id,
+        }
+        .emit(&app);
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_task_dependencies(
+    db: State<'_, Database>,
+    task_id: String,
+) -> Result<TaskDependencies, String> {
+    run_blocking(&db, move |db| {
+        dependencies::get_task_dependencies(db, &task_id)
+    })
+    .await
+}
@@ -0,0 +1,53 @@
+use super::operations::{run_blocking, run_cancellable, Operations};
+use crate::database::{Database, Date, FocusDayStats, FocusSession, StartFocusInput};
+use crate::events::Change;
+use crate::services::focus;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn start_focus_session(
+    app: AppHandle,
+    db: State<'_, Database>,
+    input: StartFocusInput,
+) -> Result<FocusSession, String> {
+    run_blocking(&db, move |db| {
+        let session = focus::start_focus_session(db, input)?;
+        Change::FocusStarted(session.clone()).emit(&app);
+        Ok(session)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn stop_focus_session(
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<Option<FocusSession>, String> {
+    run_blocking(&db, move |db| {
+        let session = focus::stop_focus_session(db)?;
+        if let Some(ref session) = session {
+            Change::FocusStopped(session.clone()).emit(&app);
+        }
+        Ok(session)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_focus_session(db: State<'_, Database>) -> Result<Option<FocusSession>, String> {
+    run_blocking(&db, focus::get_focus_session).await
+}
+
+#[tauri::command]
+pub async fn get_focus_stats(
+    db: State<'_, Database>,
+    operations: State<'_, Operations>,
+    start_date: Date,
+    end_date: Date,
+    operation_id: Option<String>,
+) -> Result<Vec<FocusDayStats>, String> {
+    run_cancellable(&db, &operations, operation_id, move |db| {
+        focus::get_focus_stats(db, &start_date.to_string(), &end_date.to_string())
+    })
+    .await
+}
@@ -1,128 +1,299 @@
-use crate::database::{DailyGoal, Database, UpsertGoalInput};
-use rusqlite::params;
-use tauri::State;
-use uuid::Uuid;
-
-#[tauri::command]
-pub fn get_goals_for_date(db: State<Database>, date: String) -> Result<Vec<DailyGoal>, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, goal_date, goal_text, goal_order, is_completed
-             FROM daily_goals
-             WHERE goal_date = ?
-             ORDER BY goal_order",
-        )
-        .map_err(|e| e.to_string())?;
-
-    let goals = stmt
-        .query_map(params![date], |row| {
-            Ok(DailyGoal {
-                id: row.get(0)?,
-                goal_date: row.get(1)?,
-                goal_text: row.get(2)?,
-                goal_order: row.get(3)?,
-                is_completed: row.get::<_, i32>(4)? != 0,
-            })
-        })
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())?;
-
-    Ok(goals)
-}
-
-#[tauri::command]
-pub fn upsert_goal(db: State<Database>, input: UpsertGoalInput) -> Result<DailyGoal, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-
-    // Check if goal exists for this date and order
-    let existing: Option<String> = conn
-        .query_row(
-            "SELECT id FROM daily_goals WHERE goal_date = ? AND goal_order = ?",
-            params![input.goal_date, input.goal_order],
-            |row| row.get(0),
-        )
-        .ok();
-
-    let id = if let Some(existing_id) = existing {
-        // Update existing goal
-        conn.execute(
-            "UPDATE daily_goals SET goal_text = ? WHERE id = ?",
-            params![input.goal_text, existing_id],
-        )
-        .map_err(|e| e.to_string())?;
-        existing_id
-    } else {
-        // Create new goal
-        let new_id = Uuid::new_v4().to_string();
-        conn.execute(
-            "INSERT INTO daily_goals (id, goal_date, goal_text, goal_order)
-             VALUES (?, ?, ?, ?)",
-            params![new_id, input.goal_date, input.goal_text, input.goal_order],
-        )
-        .map_err(|e| e.to_string())?;
-        new_id
-    };
-
-    // Fetch and return the goal
-    let goal = conn
-        .query_row(
-            "SELECT id, goal_date, goal_text, goal_order, is_completed
-             FROM daily_goals WHERE id = ?",
-            params![id],
-            |row| {
-                Ok(DailyGoal {
-                    id: row.get(0)?,
-                    goal_date: row.get(1)?,
-                    goal_text: row.get(2)?,
-                    goal_order: row.get(3)?,
-                    is_completed: row.get::<_, i32>(4)? != 0,
-                })
-            },
-        )
-        .map_err(|e| e.to_string())?;
-
-    Ok(goal)
-}
-
-#[tauri::command]
-pub fn toggle_goal_completion(db: State<Database>, id: String) -> Result<DailyGoal, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-
-    conn.execute(
-        "UPDATE daily_goals SET is_completed = NOT is_completed WHERE id = ?",
-        params![id],
-    )
-    .map_err(|e| e.to_string())?;
-
-    let goal = conn
-        .query_row(
-            "SELECT id, goal_date, goal_text, goal_order, is_completed
-             FROM daily_goals WHERE id = ?",
-            params![id],
-            |row| {
-                Ok(DailyGoal {
-                    id: row.get(0)?,
-                    goal_date: row.get(1)?,
-                    goal_text: row.get(2)?,
-                    goal_order: row.get(3)?,
-                    is_completed: row.get::<_, i32>(4)? != 0,
-                })
-            },
-        )
-        .map_err(|e| e.to_string())?;
-
-    Ok(goal)
-}
-
-#[tauri::command]
-pub fn delete_goal(db: State<Database>, id: String) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-
-    conn.execute("DELETE FROM daily_goals WHERE id = ?", params![id])
-        .map_err(|e| e.to_string())?;
-
-    Ok(())
+use super::operations::{run_blocking, run_cancellable, Operations};
+use crate::database::{
+    DailyGoal, Database, Date, GoalStats, InsertGoalInput, PeriodGoal, PeriodKey, UpsertGoalInput,
+    Task, UpsertPeriodGoalInput,
+};
+use crate::events::Change;
+use crate::services::goals;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn get_goals_for_date(
+    db: State<'_, Database>,
+    date: Date,
+) -> Result<Vec<DailyGoal>, String> {
+    run_blocking(&db, move |db| {
+        goals::get_goals_for_date(db, &date.to_string())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn upsert_goal(
+    app: AppHandle,
+    db: State<'_, Database>,
+    input: UpsertGoalInput,
+) -> Result<DailyGoal, String> {
+    run_blocking(&db, move |db| {
+        let goal = goals::upsert_goal(db, input)?;
+        Change::GoalSaved(goal.clone()).emit(&app);
+        emit_parent(&app, db, &goal);
+        Ok(goal)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn insert_goal(
+    app: AppHandle,
+    db: State<'_, Database>,
+    input: InsertGoalInput,
+) -> Result<DailyGoal, String> {
+    run_blocking(&db, move |db| {
+        let goal = goals::insert_goal(db, input)?;
+        Change::GoalSaved(goal.clone()).emit(&app);
+        emit_parent(&app, db, &goal);
+        // Goals after it moved down a slot
+        let day = goals::get_goals_for_date(db, &goal.goal_date)?;
+        Change::GoalsReordered {
+            date: goal.goal_date.clone(),
+            goals: day,
+        }
+        .emit(&app);
+        Ok(goal)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn reorder_goals(
+    app: AppHandle,
+    db: State<'_, Database>,
+    date: Date,
+    ordered_ids: Vec<String>,
+) -> Result<Vec<DailyGoal>, String> {
+    run_blocking(&db, move |db| {
+        let date = date.to_string();
+        let goals = goals::reorder_goals(db, &date, &ordered_ids)?;
+        Change::GoalsReordered {
+            date,
+            goals: goals.clone(),
+        }
+        .emit(&app);
+        Ok(goals)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn toggle_goal_completion(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+) -> Result<DailyGoal, String> {
+    run_blocking(&db, move |db| {
+        let goal = goals::toggle_goal_completion(db, &id)?;
+        Change::GoalToggled(goal.clone()).emit(&app);
+        emit_parent(&app, db, &goal);
+        Ok(goal)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn delete_goal(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+) -> Result<(), String> {
+    run_blocking(&db, move |db| {
+        let goal = goals::delete_goal(db, &id)?;
+        Change::GoalDeleted { id }.emit(&app);
+        emit_parent(&app, db, &goal);
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn set_goal_parent(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+    parent_goal_id: Option<String>,
+) -> Result<DailyGoal, String> {
+    run_blocking(&db, move |db| {
+        let goal = goals::set_goal_parent(db, &id, parent_goal_id.as_deref())?;
+        Change::GoalSaved(goal.clone()).emit(&app);
+        emit_parent(&app, db, &goal);
+        Ok(goal)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn link_task_to_goal(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+    task_id: String,
+) -> Result<DailyGoal, String> {
+    run_blocking(&db, move |db| {
+        let goal = goals::link_task_to_goal(db, &id, &task_id)?;
+        Change::GoalSaved(goal.clone()).emit(&app);
+        emit_parent(&app, db, &goal);
+        Ok(goal)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn unlink_task_from_goal(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+    task_id: String,
+) -> Result<DailyGoal, String> {
+    run_blocking(&db, move |db| {
+        let goal = goals::unlink_task_from_goal(db, &id, &task_id)?;
+        Change::GoalSaved(goal.clone()).emit(&app);
+        emit_parent(&app, db, &goal);
+        Ok(goal)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_goal_tasks(db: State<'_, Database>, id: String) -> Result<Vec<Task>, String> {
+    run_blocking(&db, move |db| goals::get_goal_tasks(db, &id)).await
+}
+
+#[tauri::command]
+pub async fn set_goal_auto_complete(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+    auto_complete: bool,
+) -> Result<DailyGoal, String> {
+    run_blocking(&db, move |db| {
+        let goal = goals::set_goal_auto_complete(db, &id, auto_complete)?;
+        Change::GoalSaved(goal.clone()).emit(&app);
+        emit_parent(&app, db, &goal);
+        Ok(goal)
+    })
+    .await
+}
+
+/// Auto-completing goals follow their linked tasks, so a task changing can
+/// complete or reopen them.
+pub(crate) fn emit_synced_goals(app: &AppHandle, db: &Database, synced: Vec<DailyGoal>) {
+    for goal in synced {
+        Change::GoalToggled(goal.clone()).emit(app);
+        emit_parent(app, db, &goal);
+    }
+}
+
+/// A day's goal changing moves the progress of the period goal it counts
+/// towards.
+pub(crate) fn emit_parent(app: &AppHandle, db: &Database, goal: &DailyGoal) {
+    if let Some(ref parent_goal_id) = goal.parent_goal_id {
+        if let Ok(parent) = goals::get_period_goal(db, parent_goal_id) {
+            Change::PeriodGoalUpdated(parent).emit(app);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_goals_for_period(
+    db: State<'_, Database>,
+    period_key: PeriodKey,
+) -> Result<Vec<PeriodGoal>, String> {
+    run_blocking(&db, move |db| goals::get_goals_for_period(db, &period_key)).await
+}
+
+#[tauri::command]
+pub async fn upsert_period_goal(
+    app: AppHandle,
+    db: State<'_, Database>,
+    input: UpsertPeriodGoalInput,
+) -> Result<PeriodGoal, String> {
+    run_blocking(&db, move |db| {
+        let goal = goals::upsert_period_goal(db, input)?;
+        Change::PeriodGoalSaved(goal.clone()).emit(&app);
+        Ok(goal)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn toggle_period_goal_completion(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+) -> Result<PeriodGoal, String> {
+    run_blocking(&db, move |db| {
+        let goal = goals::toggle_period_goal_completion(db, &id)?;
+        Change::PeriodGoalToggled(goal.clone()).emit(&app);
+        Ok(goal)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn delete_period_goal(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+) -> Result<(), String> {
+    run_blocking(&db, move |db| {
+        goals::delete_period_goal(db, &id)?;
+        Change::PeriodGoalDeleted { id }.emit(&app);
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn carry_over_goals(
+    app: AppHandle,
+    db: State<'_, Database>,
+    from_date: Date,
+    to_date: Date,
+) -> Result<Vec<DailyGoal>, String> {
+    run_blocking(&db, move |db| {
+        let carried = goals::carry_over_goals(db, &from_date.to_string(), &to_date.to_string())?;
+        for goal in &carried {
+            Change::GoalSaved(goal.clone()).emit(&app);
+        }
+        Ok(carried)
+    })
+    .await
+}
+
+/// Whether unfinished goals are carried to the next day automatically.
+#[tauri::command]
+pub async fn get_goal_carry_over_policy(db: State<'_, Database>) -> Result<bool, String> {
+    run_blocking(&db, goals::get_goal_carry_over).await
+}
+
+#[tauri::command]
+pub async fn set_goal_carry_over_policy(
+    app: AppHandle,
+    db: State<'_, Database>,
+    enabled: bool,
+) -> Result<bool, String> {
+    run_blocking(&db, move |db| {
+        goals::set_goal_carry_over(db, enabled)?;
+        Change::SettingChanged {
+            key: goals::CARRY_OVER_SETTING.to_string(),
+            value: enabled.to_string(),
+        }
+        .emit(&app);
+        Ok(enabled)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_goal_stats(
+    db: State<'_, Database>,
+    operations: State<'_, Operations>,
+    start_date: Date,
+    end_date: Date,
+    operation_id: Option<String>,
+) -> Result<GoalStats, String> {
+    run_cancellable(&db, &operations, operation_id, move |db| {
+        goals::get_goal_stats(db, &start_date.to_string(), &end_date.to_string())
+    })
+    .await
 }
@@ -0,0 +1,132 @@
+use super::operations::{run_blocking, run_cancellable, Operations};
+use crate::database::{Database, Date, Habit, HabitCheckIn, HabitInput, HabitStats, HabitStatus};
+use crate::events::Change;
+use crate::services::habits;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn get_habits(
+    db: State<'_, Database>,
+    include_archived: Option<bool>,
+) -> Result<Vec<Habit>, String> {
+    run_blocking(&db, move |db| {
+        habits::get_habits(db, include_archived.unwrap_or(false))
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_habits_for_date(
+    db: State<'_, Database>,
+    date: Date,
+) -> Result<Vec<HabitStatus>, String> {
+    run_blocking(&db, move |db| habits::get_habits_for_date(db, date.0)).await
+}
+
+#[tauri::command]
+pub async fn create_habit(
+    app: AppHandle,
+    db: State<'_, Database>,
+    input: HabitInput,
+) -> Result<Habit, String> {
+    run_blocking(&db, move |db| {
+        let habit = habits::create_habit(db, input)?;
+        Change::HabitSaved(habit.clone()).emit(&app);
+        Ok(habit)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn update_habit(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+    input: HabitInput,
+) -> Result<Habit, String> {
+    run_blocking(&db, move |db| {
+        let habit = habits::update_habit(db, &id, input)?;
+        Change::HabitSaved(habit.clone()).emit(&app);
+        Ok(habit)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn set_habit_archived(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+    archived: bool,
+) -> Result<Habit, String> {
+    run_blocking(&db, move |db| {
+        let habit = habits::set_habit_archived(db, &id, archived)?;
+        Change::HabitSaved(habit.clone()).emit(&app);
+        Ok(habit)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn delete_habit(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+) -> Result<(), String> {
+    run_blocking(&db, move |db| {
+        habits::delete_habit(db, &id)?;
+        Change::HabitDeleted { id }.emit(&app);
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn check_in_habit(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+    date: Date,
+    value: Option<f64>,
+) -> Result<HabitCheckIn, String> {
+    run_blocking(&db, move |db| {
+        let check_in = habits::check_in_habit(db, &id, &date.to_string(), value)?;
+        Change::HabitCheckedIn(check_in.clone()).emit(&app);
+        Ok(check_in)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn remove_habit_check_in(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+    date: Date,
+) -> Result<(), String> {
+    run_blocking(&db, move |db| {
+        habits::remove_habit_check_in(db, &id, &date.to_string())?;
+        Change::HabitCheckInRemoved {
+            habit_id: id,
+            checkin_date: date.to_string(),
+        }
+        .emit(&app);
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_habit_stats(
+    db: State<'_, Database>,
+    operations: State<'_, Operations>,
+    id: String,
+    start_date: Date,
+    end_date: Date,
+    operation_id: Option<String>,
+) -> Result<HabitStats, String> {
+    run_cancellable(&db, &operations, operation_id, move |db| {
+        habits::get_habit_stats(db, &id, start_date.0, end_date.0)
+    })
+    .await
+}
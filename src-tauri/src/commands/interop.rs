@@ -0,0 +1,69 @@
+use super::operations::{run_cancellable, Operations};
+use crate::database::Database;
+use crate::events::Change;
+use crate::interop::ImportReport;
+use crate::services::interop;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn import_day_one(
+    app: AppHandle,
+    db: State<'_, Database>,
+    operations: State<'_, Operations>,
+    path: String,
+    operation_id: Option<String>,
+) -> Result<ImportReport, String> {
+    run_cancellable(&db, &operations, operation_id, move |db| {
+        let report = interop::import_day_one(db, Path::new(&path))?;
+        Change::DataImported(report.clone()).emit(&app);
+        Ok(report)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn import_markdown_folder(
+    app: AppHandle,
+    db: State<'_, Database>,
+    operations: State<'_, Operations>,
+    path: String,
+    operation_id: Option<String>,
+) -> Result<ImportReport, String> {
+    run_cancellable(&db, &operations, operation_id, move |db| {
+        let report = interop::import_markdown_folder(db, Path::new(&path))?;
+        Change::DataImported(report.clone()).emit(&app);
+        Ok(report)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn import_todo_txt(
+    app: AppHandle,
+    db: State<'_, Database>,
+    operations: State<'_, Operations>,
+    content: String,
+    operation_id: Option<String>,
+) -> Result<ImportReport, String> {
+    run_cancellable(&db, &operations, operation_id, move |db| {
+        let report = interop::import_todo_txt(db, &content)?;
+        Change::DataImported(report.clone()).emit(&app);
+        Ok(report)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn export_todo_txt(
+    db: State<'_, Database>,
+    operations: State<'_, Operations>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    operation_id: Option<String>,
+) -> Result<String, String> {
+    run_cancellable(&db, &operations, operation_id, move |db| {
+        interop::export_todo_txt(db, start_date.as_deref(), end_date.as_deref())
+    })
+    .await
+}
@@ -1,94 +1,100 @@
-use crate::database::{Database, JournalEntry, UpsertEntryInput};
-use rusqlite::params;
-use tauri::State;
-use uuid::Uuid;
+use super::operations::{run_blocking, run_cancellable, Operations};
+use crate::database::{
+    CreateEntryInput, Database, Date, JournalEntry, MoodReport, UpdateEntryInput, UpsertEntryInput,
+};
+use crate::events::Change;
+use crate::services::journal;
+use tauri::{AppHandle, State};
 
 #[tauri::command]
-pub fn get_entry_for_date(
-    db: State<Database>,
-    date: String,
+pub async fn get_entry_for_date(
+    db: State<'_, Database>,
+    date: Date,
 ) -> Result<Option<JournalEntry>, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-
-    let entry = conn
-        .query_row(
-            "SELECT id, entry_date, content, mood
-             FROM journal_entries
-             WHERE entry_date = ?",
-            params![date],
-            |row| {
-                Ok(JournalEntry {
-                    id: row.get(0)?,
-                    entry_date: row.get(1)?,
-                    content: row.get(2)?,
-                    mood: row.get(3)?,
-                })
-            },
-        )
-        .ok();
-
-    Ok(entry)
+    run_blocking(&db, move |db| {
+        journal::get_entry_for_date(db, &date.to_string())
+    })
+    .await
 }
 
 #[tauri::command]
-pub fn upsert_entry(db: State<Database>, input: UpsertEntryInput) -> Result<JournalEntry, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-
-    // Check if entry exists for this date
-    let existing: Option<String> = conn
-        .query_row(
-            "SELECT id FROM journal_entries WHERE entry_date = ?",
-            params![input.entry_date],
-            |row| row.get(0),
-        )
-        .ok();
-
-    let id = if let Some(existing_id) = existing {
-        // Update existing entry
-        conn.execute(
-            "UPDATE journal_entries SET content = ?, mood = ? WHERE id = ?",
-            params![input.content, input.mood, existing_id],
-        )
-        .map_err(|e| e.to_string())?;
-        existing_id
-    } else {
-        // Create new entry
-        let new_id = Uuid::new_v4().to_string();
-        conn.execute(
-            "INSERT INTO journal_entries (id, entry_date, content, mood)
-             VALUES (?, ?, ?, ?)",
-            params![new_id, input.entry_date, input.content, input.mood],
-        )
-        .map_err(|e| e.to_string())?;
-        new_id
-    };
+pub async fn upsert_entry(
+    app: AppHandle,
+    db: State<'_, Database>,
+    input: UpsertEntryInput,
+) -> Result<JournalEntry, String> {
+    run_blocking(&db, move |db| {
+        let entry = journal::upsert_entry(db, input)?;
+        Change::EntrySaved(entry.clone()).emit(&app);
+        Ok(entry)
+    })
+    .await
+}
 
-    // Fetch and return the entry
-    let entry = conn
-        .query_row(
-            "SELECT id, entry_date, content, mood
-             FROM journal_entries WHERE id = ?",
-            params![id],
-            |row| {
-                Ok(JournalEntry {
-                    id: row.get(0)?,
-                    entry_date: row.get(1)?,
-                    content: row.get(2)?,
-                    mood: row.get(3)?,
-                })
-            },
-        )
-        .map_err(|e| e.to_string())?;
+#[tauri::command]
+pub async fn list_entries(
+    db: State<'_, Database>,
+    start_date: Date,
+    end_date: Date,
+) -> Result<Vec<JournalEntry>, String> {
+    run_blocking(&db, move |db| {
+        journal::list_entries(db, &start_date.to_string(), &end_date.to_string())
+    })
+    .await
+}
 
-    Ok(entry)
+#[tauri::command]
+pub async fn create_entry(
+    app: AppHandle,
+    db: State<'_, Database>,
+    input: CreateEntryInput,
+) -> Result<JournalEntry, String> {
+    run_blocking(&db, move |db| {
+        let entry = journal::create_entry(db, input)?;
+        Change::EntrySaved(entry.clone()).emit(&app);
+        Ok(entry)
+    })
+    .await
 }
 
 #[tauri::command]
-pub fn delete_entry(db: State<Database>, id: String) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+pub async fn update_entry(
+    app: AppHandle,
+    db: State<'_, Database>,
+    input: UpdateEntryInput,
+) -> Result<JournalEntry, String> {
+    run_blocking(&db, move |db| {
+        let entry = journal::update_entry(db, input)?;
+        Change::EntrySaved(entry.clone()).emit(&app);
+        Ok(entry)
+    })
+    .await
+}
 
-    conn.execute("DELETE FROM journal_entries WHERE id = ?", params![id])
-        .map_err(|e| e.to_string())?;
+#[tauri::command]
+pub async fn delete_entry(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+) -> Result<(), String> {
+    run_blocking(&db, move |db| {
+        journal::delete_entry(db, &id)?;
+        Change::EntryDeleted { id }.emit(&app);
+        Ok(())
+    })
+    .await
+}
 
-    Ok(())
+#[tauri::command]
+pub async fn get_mood_report(
+    db: State<'_, Database>,
+    operations: State<'_, Operations>,
+    start_date: Date,
+    end_date: Date,
+    operation_id: Option<String>,
+) -> Result<MoodReport, String> {
+    run_cancellable(&db, &operations, operation_id, move |db| {
+        journal::get_mood_report(db, &start_date.to_string(), &end_date.to_string())
+    })
+    .await
 }
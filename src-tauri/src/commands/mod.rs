@@ -1,7 +1,23 @@
 pub mod tasks;
 pub mod goals;
+pub mod habits;
 pub mod journal;
+pub mod interop;
+pub mod time_tracking;
+pub mod focus;
+pub mod scheduling;
+pub mod dependencies;
+pub mod wellbeing;
+pub mod operations;
 
 pub use tasks::*;
 pub use goals::*;
+pub use habits::*;
 pub use journal::*;
+pub use interop::*;
+pub use time_tracking::*;
+pub use focus::*;
+pub use scheduling::*;
+pub use dependencies::*;
+pub use wellbeing::*;
+pub use operations::*;
@@ -0,0 +1,136 @@
+use crate::database::{CancelToken, Database};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use tauri::State;
+
+/// Long-running operations that can still be cancelled, keyed by an id the
+/// frontend picks when it starts one.
+#[derive(Default)]
+pub struct Operations(Mutex<HashMap<String, CancelToken>>);
+
+impl Operations {
+    fn tokens(&self) -> MutexGuard<'_, HashMap<String, CancelToken>> {
+        // The map stays consistent even if a holder panicked
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn cancel(&self, id: &str) -> bool {
+        match self.tokens().get(id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Runs database work on the blocking thread pool, so a slow query doesn't
+/// hold up IPC and make the window stutter.
+pub(crate) async fn run_blocking<T, F>(db: &Database, work: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&Database) -> Result<T, String> + Send + 'static,
+{
+    let db = db.clone();
+    tauri::async_runtime::spawn_blocking(move || work(&db))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Like `run_blocking`, but when given an `operation_id` the work can be
+/// stopped with `cancel_operation` until it finishes. An id can only be used
+/// by one running operation at a time.
+pub(crate) async fn run_cancellable<T, F>(
+    db: &Database,
+    operations: &Operations,
+    operation_id: Option<String>,
+    work: F,
+) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&Database) -> Result<T, String> + Send + 'static,
+{
+    let Some(id) = operation_id else {
+        return run_blocking(db, work).await;
+    };
+
+    let token = CancelToken::default();
+    match operations.tokens().entry(id.clone()) {
+        Entry::Occupied(_) => return Err(format!("Operation \"{}\" is already running", id)),
+        Entry::Vacant(entry) => {
+            entry.insert(token.clone());
+        }
+    }
+    let result = run_blocking(&db.cancellable(&token), work).await;
+    operations.tokens().remove(&id);
+
+    match result {
+        Err(_) if token.is_cancelled() => Err("Cancelled".to_string()),
+        result => result,
+    }
+}
+
+/// Returns whether an operation with that id was running.
+#[tauri::command]
+pub fn cancel_operation(operations: State<Operations>, operation_id: String) -> bool {
+    operations.cancel(&operation_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Counts forever, so it only ends if interrupted.
+    fn endless_query(db: &Database) -> Result<i64, String> {
+        let conn = db.conn()?;
+        conn.query_row(
+            "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n)
+             SELECT COUNT(*) FROM n",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn cancelling_stops_the_query_and_forgets_the_id() {
+        let db = Database::in_memory().unwrap();
+        let operations = Arc::new(Operations::default());
+
+        let running = {
+            let operations = operations.clone();
+            tauri::async_runtime::spawn(async move {
+                run_cancellable(&db, &operations, Some("count".to_string()), endless_query).await
+            })
+        };
+
+        while !operations.cancel("count") {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        let result = tauri::async_runtime::block_on(running).unwrap();
+
+        assert_eq!(result, Err("Cancelled".to_string()));
+        assert!(!operations.cancel("count"));
+        assert!(operations.tokens().is_empty());
+    }
+
+    #[test]
+    fn finished_operations_release_their_id() {
+        let db = Database::in_memory().unwrap();
+        let operations = Operations::default();
+
+        let result = tauri::async_runtime::block_on(run_cancellable(
+            &db,
+            &operations,
+            Some("quick".to_string()),
+            |_| Ok(1),
+        ));
+
+        assert_eq!(result, Ok(1));
+        assert!(!operations.cancel("quick"));
+    }
+}
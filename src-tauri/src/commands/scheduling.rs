@@ -0,0 +1,63 @@
+use super::operations::run_blocking;
+use crate::database::{
+    ApplyScheduleInput, AutoScheduleInput, Database, Date, SchedulePlan, Task, TaskConflict, Time,
+};
+use crate::events::Change;
+use crate::services::scheduling;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn find_conflicts(
+    db: State<'_, Database>,
+    start_date: Date,
+    end_date: Date,
+) -> Result<Vec<TaskConflict>, String> {
+    run_blocking(&db, move |db| {
+        scheduling::find_conflicts(db, &start_date.to_string(), &end_date.to_string())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn find_slot_conflicts(
+    db: State<'_, Database>,
+    task_id: Option<String>,
+    scheduled_date: Date,
+    scheduled_time: Time,
+    duration_minutes: i32,
+) -> Result<Vec<TaskConflict>, String> {
+    run_blocking(&db, move |db| {
+        scheduling::find_slot_conflicts(
+            db,
+            task_id.as_deref(),
+            &scheduled_date.to_string(),
+            &scheduled_time.to_string(),
+            duration_minutes,
+        )
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn auto_schedule_day(
+    db: State<'_, Database>,
+    input: AutoScheduleInput,
+) -> Result<SchedulePlan, String> {
+    run_blocking(&db, move |db| scheduling::auto_schedule_day(db, input)).await
+}
+
+#[tauri::command]
+pub async fn apply_schedule(
+    app: AppHandle,
+    db: State<'_, Database>,
+    input: ApplyScheduleInput,
+) -> Result<Vec<Task>, String> {
+    run_blocking(&db, move |db| {
+        let applied = scheduling::apply_schedule(db, input)?;
+        for task in &applied {
+            Change::TaskUpdated(task.clone()).emit(&app);
+        }
+        Ok(applied)
+    })
+    .await
+}
@@ -1,232 +1,134 @@
-use crate::database::{CreateTaskInput, Database, Task, UpdateTaskInput};
-use rusqlite::params;
-use tauri::State;
-use uuid::Uuid;
+use super::goals::emit_synced_goals;
+use super::operations::run_blocking;
+use crate::database::{CreateTaskInput, Database, Date, Task, TaskWithConflicts, UpdateTaskInput};
+use crate::events::Change;
+use crate::quick_add::{self, QuickAdd};
+use crate::scheduling::zones;
+use crate::services::{dependencies, scheduling, tasks};
+use serde::Serialize;
+use tauri::{AppHandle, State};
 
 #[tauri::command]
-pub fn get_tasks_for_date_range(
-    db: State<Database>,
-    start_date: String,
-    end_date: String,
+pub async fn get_tasks_for_date_range(
+    db: State<'_, Database>,
+    start_date: Date,
+    end_date: Date,
 ) -> Result<Vec<Task>, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, title, description, scheduled_date, scheduled_time,
-                    duration_minutes, priority, is_completed, reminder_minutes, color, created_at
-             FROM tasks
-             WHERE scheduled_date >= ? AND scheduled_date <= ?
-             ORDER BY scheduled_date, scheduled_time",
-        )
-        .map_err(|e| e.to_string())?;
-
-    let tasks = stmt
-        .query_map(params![start_date, end_date], |row| {
-            Ok(Task {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                description: row.get(2)?,
-                scheduled_date: row.get(3)?,
-                scheduled_time: row.get(4)?,
-                duration_minutes: row.get(5)?,
-                priority: row.get(6)?,
-                is_completed: row.get::<_, i32>(7)? != 0,
-                reminder_minutes: row.get(8)?,
-                color: row.get(9)?,
-                created_at: row.get(10)?,
-            })
-        })
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())?;
-
-    Ok(tasks)
+    run_blocking(&db, move |db| {
+        tasks::get_tasks_for_date_range(db, &start_date.to_string(), &end_date.to_string())
+    })
+    .await
 }
 
 #[tauri::command]
-pub fn create_task(db: State<Database>, input: CreateTaskInput) -> Result<Task, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-
-    let id = Uuid::new_v4().to_string();
-    let duration = input.duration_minutes.unwrap_or(30);
-    let priority = input.priority.unwrap_or_else(|| "medium".to_string());
-
-    conn.execute(
-        "INSERT INTO tasks (id, title, description, scheduled_date, scheduled_time,
-                           duration_minutes, priority, reminder_minutes, color)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
-        params![
-            id,
-            input.title,
-            input.description,
-            input.scheduled_date,
-            input.scheduled_time,
-            duration,
-            priority,
-            input.reminder_minutes,
-            input.color,
-        ],
-    )
-    .map_err(|e| e.to_string())?;
-
-    // Fetch and return the created task
-    let task = conn
-        .query_row(
-            "SELECT id, title, description, scheduled_date, scheduled_time,
-                    duration_minutes, priority, is_completed, reminder_minutes, color, created_at
-             FROM tasks WHERE id = ?",
-            params![id],
-            |row| {
-                Ok(Task {
-                    id: row.get(0)?,
-                    title: row.get(1)?,
-                    description: row.get(2)?,
-                    scheduled_date: row.get(3)?,
-                    scheduled_time: row.get(4)?,
-                    duration_minutes: row.get(5)?,
-                    priority: row.get(6)?,
-                    is_completed: row.get::<_, i32>(7)? != 0,
-                    reminder_minutes: row.get(8)?,
-                    color: row.get(9)?,
-                    created_at: row.get(10)?,
-                })
-            },
-        )
-        .map_err(|e| e.to_string())?;
+pub async fn create_task(
+    app: AppHandle,
+    db: State<'_, Database>,
+    input: CreateTaskInput,
+) -> Result<TaskWithConflicts, String> {
+    run_blocking(&db, move |db| {
+        let task = tasks::create_task(db, input)?;
+        Change::TaskCreated(task.clone()).emit(&app);
+        with_warnings(db, task)
+    })
+    .await
+}
 
-    Ok(task)
+#[tauri::command]
+pub async fn update_task(
+    app: AppHandle,
+    db: State<'_, Database>,
+    input: UpdateTaskInput,
+) -> Result<TaskWithConflicts, String> {
+    run_blocking(&db, move |db| {
+        let completing = input.is_completed == Some(true);
+        let (task, synced) = tasks::update_task(db, input)?;
+        Change::TaskUpdated(task.clone()).emit(&app);
+        if completing {
+            emit_unblocked(&app, db, &task)?;
+        }
+        emit_synced_goals(&app, db, synced);
+        with_warnings(db, task)
+    })
+    .await
 }
 
 #[tauri::command]
-pub fn update_task(db: State<Database>, input: UpdateTaskInput) -> Result<Task, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+pub async fn delete_task(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+) -> Result<(), String> {
+    run_blocking(&db, move |db| {
+        let (deleted, synced) = tasks::delete_task(db, &id)?;
+        if deleted {
+            Change::TaskDeleted { id }.emit(&app);
+        }
+        emit_synced_goals(&app, db, synced);
+        Ok(())
+    })
+    .await
+}
 
-    // Build update query dynamically based on provided fields
-    let mut updates = Vec::new();
-    let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+#[tauri::command]
+pub async fn toggle_task_completion(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+) -> Result<Task, String> {
+    run_blocking(&db, move |db| {
+        let (task, synced) = tasks::toggle_task_completion(db, &id)?;
+        Change::TaskToggled(task.clone()).emit(&app);
+        if task.is_completed {
+            emit_unblocked(&app, db, &task)?;
+        }
+        emit_synced_goals(&app, db, synced);
+        Ok(task)
+    })
+    .await
+}
 
-    if let Some(ref title) = input.title {
-        updates.push("title = ?");
-        values.push(Box::new(title.clone()));
-    }
-    if let Some(ref description) = input.description {
-        updates.push("description = ?");
-        values.push(Box::new(description.clone()));
-    }
-    if let Some(ref scheduled_date) = input.scheduled_date {
-        updates.push("scheduled_date = ?");
-        values.push(Box::new(scheduled_date.clone()));
-    }
-    if input.clear_scheduled_time {
-        updates.push("scheduled_time = NULL");
-    } else if let Some(ref scheduled_time) = input.scheduled_time {
-        updates.push("scheduled_time = ?");
-        values.push(Box::new(scheduled_time.clone()));
-    }
-    if let Some(duration) = input.duration_minutes {
-        updates.push("duration_minutes = ?");
-        values.push(Box::new(duration));
-    }
-    if let Some(ref priority) = input.priority {
-        updates.push("priority = ?");
-        values.push(Box::new(priority.clone()));
-    }
-    if let Some(is_completed) = input.is_completed {
-        updates.push("is_completed = ?");
-        values.push(Box::new(if is_completed { 1 } else { 0 }));
-    }
-    if let Some(reminder) = input.reminder_minutes {
-        updates.push("reminder_minutes = ?");
-        values.push(Box::new(reminder));
-    }
-    if let Some(ref color) = input.color {
-        updates.push("color = ?");
-        values.push(Box::new(color.clone()));
-    }
+fn with_warnings(db: &Database, task: Task) -> Result<TaskWithConflicts, String> {
+    let conflicts = scheduling::conflicts_for_task(db, &task)?;
+    let dependency_warnings = dependencies::dependency_warnings(db, &task)?;
+    Ok(TaskWithConflicts {
+        task,
+        conflicts,
+        dependency_warnings,
+    })
+}
 
-    if updates.is_empty() {
-        return Err("No fields to update".to_string());
+/// Tells the frontend which dependents a completed task just unblocked.
+fn emit_unblocked(app: &AppHandle, db: &Database, task: &Task) -> Result<(), String> {
+    let unblocked = dependencies::unblocked_dependents(db, &task.id)?;
+    if !unblocked.is_empty() {
+        Change::TasksUnblocked(unblocked).emit(app);
     }
+    Ok(())
+}
 
-    let query = format!("UPDATE tasks SET {} WHERE id = ?", updates.join(", "));
-    values.push(Box::new(input.id.clone()));
-
-    let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
-    conn.execute(&query, params.as_slice())
-        .map_err(|e| e.to_string())?;
-
-    // Fetch and return the updated task
-    let task = conn
-        .query_row(
-            "SELECT id, title, description, scheduled_date, scheduled_time,
-                    duration_minutes, priority, is_completed, reminder_minutes, color, created_at
-             FROM tasks WHERE id = ?",
-            params![input.id],
-            |row| {
-                Ok(Task {
-                    id: row.get(0)?,
-                    title: row.get(1)?,
-                    description: row.get(2)?,
-                    scheduled_date: row.get(3)?,
-                    scheduled_time: row.get(4)?,
-                    duration_minutes: row.get(5)?,
-                    priority: row.get(6)?,
-                    is_completed: row.get::<_, i32>(7)? != 0,
-                    reminder_minutes: row.get(8)?,
-                    color: row.get(9)?,
-                    created_at: row.get(10)?,
-                })
-            },
-        )
-        .map_err(|e| e.to_string())?;
-
-    Ok(task)
+#[derive(Debug, Serialize)]
+pub struct QuickAddResult {
+    pub task: Task,
+    pub parsed: QuickAdd,
 }
 
+/// Shows how a quick-add line would be read, without creating anything.
 #[tauri::command]
-pub fn delete_task(db: State<Database>, id: String) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-
-    conn.execute("DELETE FROM tasks WHERE id = ?", params![id])
-        .map_err(|e| e.to_string())?;
-
-    Ok(())
+pub fn parse_quick_add(text: String) -> QuickAdd {
+    quick_add::parse(&text, zones::today())
 }
 
 #[tauri::command]
-pub fn toggle_task_completion(db: State<Database>, id: String) -> Result<Task, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-
-    conn.execute(
-        "UPDATE tasks SET is_completed = NOT is_completed WHERE id = ?",
-        params![id],
-    )
-    .map_err(|e| e.to_string())?;
-
-    let task = conn
-        .query_row(
-            "SELECT id, title, description, scheduled_date, scheduled_time,
-                    duration_minutes, priority, is_completed, reminder_minutes, color, created_at
-             FROM tasks WHERE id = ?",
-            params![id],
-            |row| {
-                Ok(Task {
-                    id: row.get(0)?,
-                    title: row.get(1)?,
-                    description: row.get(2)?,
-                    scheduled_date: row.get(3)?,
-                    scheduled_time: row.get(4)?,
-                    duration_minutes: row.get(5)?,
-                    priority: row.get(6)?,
-                    is_completed: row.get::<_, i32>(7)? != 0,
-                    reminder_minutes: row.get(8)?,
-                    color: row.get(9)?,
-                    created_at: row.get(10)?,
-                })
-            },
-        )
-        .map_err(|e| e.to_string())?;
-
-    Ok(task)
+pub async fn quick_add_task(
+    app: AppHandle,
+    db: State<'_, Database>,
+    text: String,
+) -> Result<QuickAddResult, String> {
+    run_blocking(&db, move |db| {
+        let (task, parsed) = tasks::quick_add_task(db, &text, zones::today())?;
+        Change::TaskCreated(task.clone()).emit(&app);
+        Ok(QuickAddResult { task, parsed })
+    })
+    .await
 }
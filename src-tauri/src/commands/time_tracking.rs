@@ -0,0 +1,68 @@
+use super::operations::{run_blocking, run_cancellable, Operations};
+use crate::database::{Database, Date, TimeEntry, TimeReportPeriod};
+use crate::events::Change;
+use crate::services::time_tracking;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn start_timer(
+    app: AppHandle,
+    db: State<'_, Database>,
+    task_id: String,
+) -> Result<TimeEntry, String> {
+    run_blocking(&db, move |db| {
+        let entry = time_tracking::start_timer(db, &task_id)?;
+        Change::TimerStarted(entry.clone()).emit(&app);
+        Ok(entry)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn stop_timer(
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<Option<TimeEntry>, String> {
+    run_blocking(&db, move |db| {
+        let entry = time_tracking::stop_timer(db)?;
+        if let Some(ref entry) = entry {
+            Change::TimerStopped(entry.clone()).emit(&app);
+        }
+        Ok(entry)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_running_timer(db: State<'_, Database>) -> Result<Option<TimeEntry>, String> {
+    run_blocking(&db, time_tracking::get_running_timer).await
+}
+
+#[tauri::command]
+pub async fn get_time_entries(
+    db: State<'_, Database>,
+    task_id: String,
+) -> Result<Vec<TimeEntry>, String> {
+    run_blocking(&db, move |db| time_tracking::get_time_entries(db, &task_id)).await
+}
+
+/// `group_by` is `"day"` (the default) or `"week"`.
+#[tauri::command]
+pub async fn get_time_report(
+    db: State<'_, Database>,
+    operations: State<'_, Operations>,
+    start_date: Date,
+    end_date: Date,
+    group_by: Option<String>,
+    operation_id: Option<String>,
+) -> Result<Vec<TimeReportPeriod>, String> {
+    let by_week = match group_by.as_deref() {
+        None | Some("day") => false,
+        Some("week") => true,
+        Some(other) => return Err(format!("Unknown grouping: {}", other)),
+    };
+    run_cancellable(&db, &operations, operation_id, move |db| {
+        time_tracking::get_time_report(db, &start_date.to_string(), &end_date.to_string(), by_week)
+    })
+    .await
+}
@@ -0,0 +1,170 @@
+use super::operations::run_blocking;
+use crate::database::{
+    Database, Date, WellbeingCheckIn, WellbeingCheckInInput, WellbeingDay, WellbeingMetric,
+    WellbeingMetricInput,
+};
+use crate::events::Change;
+use crate::services::wellbeing;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn get_wellbeing(
+    db: State<'_, Database>,
+    start_date: Date,
+    end_date: Date,
+) -> Result<Vec<WellbeingDay>, String> {
+    run_blocking(&db, move |db| {
+        wellbeing::get_wellbeing(db, &start_date.to_string(), &end_date.to_string())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_wellbeing_day(
+    db: State<'_, Database>,
+    date: Date,
+) -> Result<WellbeingDay, String> {
+    run_blocking(&db, move |db| {
+        wellbeing::get_wellbeing_day(db, &date.to_string())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn add_wellbeing_check_in(
+    app: AppHandle,
+    db: State<'_, Database>,
+    input: WellbeingCheckInInput,
+) -> Result<WellbeingCheckIn, String> {
+    run_blocking(&db, move |db| {
+        let check_in = wellbeing::add_wellbeing_check_in(db, input)?;
+        emit_day(&app, db, &check_in.entry_date);
+        Ok(check_in)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn update_wellbeing_check_in(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+    input: WellbeingCheckInInput,
+) -> Result<WellbeingCheckIn, String> {
+    run_blocking(&db, move |db| {
+        let previous = wellbeing::get_wellbeing_check_in(db, &id)?;
+        let check_in = wellbeing::update_wellbeing_check_in(db, &id, input)?;
+        emit_day(&app, db, &check_in.entry_date);
+        if previous.entry_date != check_in.entry_date {
+            emit_day(&app, db, &previous.entry_date);
+        }
+        Ok(check_in)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn delete_wellbeing_check_in(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+) -> Result<(), String> {
+    run_blocking(&db, move |db| {
+        let check_in = wellbeing::delete_wellbeing_check_in(db, &id)?;
+        emit_day(&app, db, &check_in.entry_date);
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn set_sleep_hours(
+    app: AppHandle,
+    db: State<'_, Database>,
+    date: Date,
+    hours: Option<f64>,
+) -> Result<WellbeingDay, String> {
+    run_blocking(&db, move |db| {
+        let date = date.to_string();
+        wellbeing::set_sleep_hours(db, &date, hours)?;
+        let day = wellbeing::get_wellbeing_day(db, &date)?;
+        Change::WellbeingUpdated(day.clone()).emit(&app);
+        Ok(day)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_wellbeing_metrics(
+    db: State<'_, Database>,
+) -> Result<Vec<WellbeingMetric>, String> {
+    run_blocking(&db, wellbeing::get_wellbeing_metrics).await
+}
+
+#[tauri::command]
+pub async fn create_wellbeing_metric(
+    app: AppHandle,
+    db: State<'_, Database>,
+    input: WellbeingMetricInput,
+) -> Result<WellbeingMetric, String> {
+    run_blocking(&db, move |db| {
+        let metric = wellbeing::create_wellbeing_metric(db, input)?;
+        Change::WellbeingMetricSaved(metric.clone()).emit(&app);
+        Ok(metric)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn update_wellbeing_metric(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+    input: WellbeingMetricInput,
+) -> Result<WellbeingMetric, String> {
+    run_blocking(&db, move |db| {
+        let metric = wellbeing::update_wellbeing_metric(db, &id, input)?;
+        Change::WellbeingMetricSaved(metric.clone()).emit(&app);
+        Ok(metric)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn delete_wellbeing_metric(
+    app: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+) -> Result<(), String> {
+    run_blocking(&db, move |db| {
+        wellbeing::delete_wellbeing_metric(db, &id)?;
+        Change::WellbeingMetricDeleted { id }.emit(&app);
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn set_metric_value(
+    app: AppHandle,
+    db: State<'_, Database>,
+    metric_id: String,
+    date: Date,
+    value: Option<f64>,
+) -> Result<WellbeingDay, String> {
+    run_blocking(&db, move |db| {
+        let date = date.to_string();
+        wellbeing::set_metric_value(db, &metric_id, &date, value)?;
+        let day = wellbeing::get_wellbeing_day(db, &date)?;
+        Change::WellbeingUpdated(day.clone()).emit(&app);
+        Ok(day)
+    })
+    .await
+}
+
+/// Windows showing the day get all of it again, averages included.
+fn emit_day(app: &AppHandle, db: &Database, date: &str) {
+    if let Ok(day) = wellbeing::get_wellbeing_day(db, date) {
+        Change::WellbeingUpdated(day).emit(app);
+    }
+}
@@ -1,13 +1,45 @@
+use crate::scheduling::zones;
+use chrono::NaiveTime;
+use r2d2::{Pool, PooledConnection};
+use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::{Connection, Result};
 use std::path::PathBuf;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
+/// Enough for a long export or search to run next to the UI's own queries
+/// and the notification scheduler without anyone waiting on it.
+const POOL_SIZE: u32 = 4;
+
+/// How many SQLite VM steps run between checks for cancellation.
+const CANCEL_CHECK_STEPS: i32 = 1000;
+
+pub type DbConnection = PooledConnection<SqliteConnectionManager>;
+
+/// Shared flag for stopping a long operation from another thread.
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Cheap to clone; clones share the pool.
+#[derive(Clone)]
 pub struct Database {
-    pub conn: Mutex<Connection>,
+    pool: Pool<SqliteConnectionManager>,
+    cancel: Option<CancelToken>,
 }
 
 impl Database {
-    pub fn new() -> Result<Self> {
+    pub fn new() -> std::result::Result<Self, String> {
         let db_path = Self::get_db_path();
 
         // Ensure the parent directory exists
@@ -15,13 +47,71 @@ impl Database {
             std::fs::create_dir_all(parent).ok();
         }
 
-        let conn = Connection::open(&db_path)?;
-        let db = Self {
-            conn: Mutex::new(conn),
-        };
+        let manager = SqliteConnectionManager::file(&db_path).with_init(Self::configure);
+        let pool = Pool::builder()
+            .max_size(POOL_SIZE)
+            .build(manager)
+            .map_err(|e| e.to_string())?;
+
+        // Every pooled connection shares the file, so migrating one is enough
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        Self::run_migrations(&conn).map_err(|e| e.to_string())?;
+
+        Ok(Self { pool, cancel: None })
+    }
+
+    /// A migrated database that lives only as long as the handle. The pool
+    /// holds a single connection, since each would otherwise get its own.
+    #[cfg(test)]
+    pub(crate) fn in_memory() -> std::result::Result<Self, String> {
+        let manager = SqliteConnectionManager::memory().with_init(|conn| {
+            conn.pragma_update(None, "foreign_keys", "ON")
+        });
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(manager)
+            .map_err(|e| e.to_string())?;
+
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        Self::run_migrations(&conn).map_err(|e| e.to_string())?;
+
+        Ok(Self { pool, cancel: None })
+    }
+
+    /// A handle whose queries fail with "interrupted" once `token` is
+    /// cancelled.
+    pub fn cancellable(&self, token: &CancelToken) -> Self {
+        Self {
+            pool: self.pool.clone(),
+            cancel: Some(token.clone()),
+        }
+    }
+
+    /// A connection from the pool, waiting for one to free up if they are
+    /// all in use.
+    pub fn conn(&self) -> std::result::Result<DbConnection, String> {
+        let conn = self.pool.get().map_err(|e| e.to_string())?;
 
-        db.run_migrations()?;
-        Ok(db)
+        // Pooled connections are reused, so the handler is set (or cleared)
+        // on every checkout rather than left over from the last operation
+        match &self.cancel {
+            Some(token) => {
+                let token = token.clone();
+                conn.progress_handler(CANCEL_CHECK_STEPS, Some(move || token.is_cancelled()));
+            }
+            None => conn.progress_handler(0, None::<fn() -> bool>),
+        }
+
+        Ok(conn)
+    }
+
+    /// Runs on every new connection. WAL lets readers carry on while another
+    /// connection writes, and the busy timeout makes a second writer wait
+    /// its turn instead of failing with "database is locked".
+    fn configure(conn: &mut Connection) -> Result<()> {
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
+        conn.pragma_update(None, "foreign_keys", "ON")?;
+        conn.busy_timeout(Duration::from_secs(5))
     }
 
     fn get_db_path() -> PathBuf {
@@ -33,9 +123,7 @@ impl Database {
         data_dir.join("journal.db")
     }
 
-    fn run_migrations(&self) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
-
+    pub(crate) fn run_migrations(conn: &Connection) -> Result<()> {
         // Create tasks table
         conn.execute(
             "CREATE TABLE IF NOT EXISTS tasks (
@@ -60,6 +148,37 @@ impl Database {
             [],
         ).ok(); // Ignore error if column already exists
 
+        // Migration: Add multi-day and all-day columns
+        conn.execute(
+            "ALTER TABLE tasks ADD COLUMN end_date TEXT",
+            [],
+        ).ok();
+
+        conn.execute(
+            "ALTER TABLE tasks ADD COLUMN is_all_day INTEGER DEFAULT 0",
+            [],
+        ).ok();
+
+        // Migration: Pin timed tasks to a time zone, starting with the one
+        // they were created in. Only done as the column is added, so a task
+        // without a zone later on isn't stamped with wherever the app runs
+        if conn
+            .execute("ALTER TABLE tasks ADD COLUMN time_zone TEXT", [])
+            .is_ok()
+        {
+            conn.execute(
+                "UPDATE tasks SET time_zone = ?
+                 WHERE time_zone IS NULL AND scheduled_time IS NOT NULL",
+                [zones::current_zone().name()],
+            )?;
+        }
+
+        // Migration: Record when a task was completed
+        conn.execute(
+            "ALTER TABLE tasks ADD COLUMN completed_at TEXT",
+            [],
+        ).ok();
+
         // Create daily_goals table
         conn.execute(
             "CREATE TABLE IF NOT EXISTS daily_goals (
@@ -73,17 +192,360 @@ impl Database {
             [],
         )?;
 
-        // Create journal_entries table
+        // Create period_goals table for week, month, quarter and year goals;
+        // the period's first and last day are kept alongside its key
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS period_goals (
+                id TEXT PRIMARY KEY,
+                period_type TEXT CHECK(period_type IN ('week', 'month', 'quarter', 'year')) NOT NULL,
+                period_key TEXT NOT NULL,
+                start_date TEXT NOT NULL,
+                end_date TEXT NOT NULL,
+                goal_text TEXT NOT NULL,
+                goal_order INTEGER NOT NULL,
+                is_completed INTEGER DEFAULT 0,
+                UNIQUE(period_key, goal_order)
+            )",
+            [],
+        )?;
+
+        // Migration: Let daily goals roll up into a period goal
+        conn.execute(
+            "ALTER TABLE daily_goals ADD COLUMN parent_goal_id TEXT
+             REFERENCES period_goals(id) ON DELETE SET NULL",
+            [],
+        ).ok();
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_goals_parent ON daily_goals(parent_goal_id)",
+            [],
+        )?;
+
+        // Migration: Track where a carried-over goal first appeared
+        conn.execute(
+            "ALTER TABLE daily_goals ADD COLUMN original_goal_id TEXT",
+            [],
+        ).ok();
+
+        conn.execute(
+            "ALTER TABLE daily_goals ADD COLUMN carried_since TEXT",
+            [],
+        ).ok();
+
+        // Create goal_tasks table: tasks whose completion a goal tracks
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS goal_tasks (
+                goal_id TEXT NOT NULL REFERENCES daily_goals(id) ON DELETE CASCADE,
+                task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+                PRIMARY KEY (goal_id, task_id)
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_goal_tasks_task ON goal_tasks(task_id)",
+            [],
+        )?;
+
+        // Migration: Let a goal complete itself once its linked tasks are done
+        conn.execute(
+            "ALTER TABLE daily_goals ADD COLUMN auto_complete INTEGER DEFAULT 0",
+            [],
+        ).ok();
+
+        // Create habits table
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS habits (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                description TEXT,
+                schedule_type TEXT NOT NULL DEFAULT 'daily'
+                    CHECK (schedule_type IN ('daily', 'weekdays', 'per_week')),
+                schedule_days TEXT,
+                times_per_week INTEGER,
+                target_value REAL,
+                unit TEXT,
+                reminder_time TEXT,
+                last_reminded_on TEXT,
+                color TEXT,
+                is_archived INTEGER DEFAULT 0,
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )?;
+
+        // Create habit_checkins table: one per habit and day
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS habit_checkins (
+                habit_id TEXT NOT NULL REFERENCES habits(id) ON DELETE CASCADE,
+                checkin_date TEXT NOT NULL,
+                value REAL,
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (habit_id, checkin_date)
+            )",
+            [],
+        )?;
+
+        // Create wellbeing_checkins table: timestamped, several per day
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS wellbeing_checkins (
+                id TEXT PRIMARY KEY,
+                entry_date TEXT NOT NULL,
+                recorded_at TEXT NOT NULL,
+                mood TEXT CHECK (mood IN ('great', 'good', 'okay', 'bad', 'terrible')),
+                energy INTEGER CHECK (energy BETWEEN 1 AND 5),
+                stress INTEGER CHECK (stress BETWEEN 1 AND 5),
+                note TEXT
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_wellbeing_checkins_date
+             ON wellbeing_checkins(entry_date, recorded_at)",
+            [],
+        )?;
+
+        // Create wellbeing_days table for once-a-day figures
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS wellbeing_days (
+                entry_date TEXT PRIMARY KEY,
+                sleep_hours REAL CHECK (sleep_hours BETWEEN 0 AND 24)
+            )",
+            [],
+        )?;
+
+        // Create wellbeing_metrics table for user-defined metrics
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS wellbeing_metrics (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL UNIQUE,
+                kind TEXT NOT NULL CHECK (kind IN ('number', 'boolean')),
+                unit TEXT,
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS wellbeing_metric_values (
+                metric_id TEXT NOT NULL REFERENCES wellbeing_metrics(id) ON DELETE CASCADE,
+                entry_date TEXT NOT NULL,
+                value REAL NOT NULL,
+                PRIMARY KEY (metric_id, entry_date)
+            )",
+            [],
+        )?;
+
+        // Create settings table for app-wide preferences
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Create journal_entries table; a day can have several entries
         conn.execute(
             "CREATE TABLE IF NOT EXISTS journal_entries (
                 id TEXT PRIMARY KEY,
-                entry_date TEXT NOT NULL UNIQUE,
+                entry_date TEXT NOT NULL,
+                title TEXT,
                 content TEXT NOT NULL,
-                mood TEXT
+                mood TEXT,
+                created_at TEXT,
+                updated_at TEXT
             )",
             [],
         )?;
 
+        // Migration: Drop the one-entry-a-day UNIQUE on entry_date. SQLite
+        // can't drop a constraint, so the table is rebuilt, keeping every
+        // entry; entries from before have no times
+        let has_title = conn
+            .prepare("SELECT 1 FROM pragma_table_info('journal_entries') WHERE name = 'title'")?
+            .exists([])?;
+        if !has_title {
+            // Rolled back when dropped, so a failed step doesn't leave the
+            // pooled connection inside an open transaction
+            let tx = conn.unchecked_transaction()?;
+            tx.execute_batch(
+                "CREATE TABLE journal_entries_new (
+                     id TEXT PRIMARY KEY,
+                     entry_date TEXT NOT NULL,
+                     title TEXT,
+                     content TEXT NOT NULL,
+                     mood TEXT,
+                     created_at TEXT,
+                     updated_at TEXT
+                 );
+                 INSERT INTO journal_entries_new (id, entry_date, content, mood)
+                 SELECT id, entry_date, content, mood FROM journal_entries;
+                 DROP TABLE journal_entries;
+                 ALTER TABLE journal_entries_new RENAME TO journal_entries;",
+            )?;
+            tx.commit()?;
+        }
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_journal_date ON journal_entries(entry_date, created_at)",
+            [],
+        )?;
+
+        // Create time_entries table; a row without ended_at is the running timer
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS time_entries (
+                id TEXT PRIMARY KEY,
+                task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+                started_at TEXT NOT NULL,
+                ended_at TEXT
+            )",
+            [],
+        )?;
+
+        // Only one timer may run at a time
+        conn.execute(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_time_entries_running
+             ON time_entries((ended_at IS NULL)) WHERE ended_at IS NULL",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_time_entries_task ON time_entries(task_id)",
+            [],
+        )?;
+
+        // Create task_dependencies table: task_id can't start until depends_on_id is done
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS task_dependencies (
+                task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+                depends_on_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+                PRIMARY KEY (task_id, depends_on_id),
+                CHECK (task_id != depends_on_id)
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_task_dependencies_prerequisite
+             ON task_dependencies(depends_on_id)",
+            [],
+        )?;
+
+        // Create focus_sessions table; a row without ended_at is the running session
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS focus_sessions (
+                id TEXT PRIMARY KEY,
+                task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+                work_minutes INTEGER NOT NULL,
+                short_break_minutes INTEGER NOT NULL,
+                long_break_minutes INTEGER NOT NULL,
+                long_break_every INTEGER NOT NULL,
+                phase TEXT CHECK(phase IN ('work', 'short_break', 'long_break')) NOT NULL,
+                phase_started_at TEXT NOT NULL,
+                completed_cycles INTEGER NOT NULL DEFAULT 0,
+                started_at TEXT NOT NULL,
+                ended_at TEXT
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_focus_sessions_running
+             ON focus_sessions((ended_at IS NULL)) WHERE ended_at IS NULL",
+            [],
+        )?;
+
+        // Create focus_cycles table, one row per completed work phase
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS focus_cycles (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL REFERENCES focus_sessions(id) ON DELETE CASCADE,
+                task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+                cycle_date TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                ended_at TEXT NOT NULL,
+                minutes INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_focus_cycles_date ON focus_cycles(cycle_date)",
+            [],
+        )?;
+
+        // Migration: Repair rows saved before input was validated. Dates and
+        // times SQLite can read are normalized; unreadable task dates fall
+        // back to the day the task was created, and unreadable times, end
+        // dates and colors are dropped
+        conn.execute(
+            "UPDATE tasks
+             SET scheduled_date = COALESCE(date(scheduled_date), date(created_at), date('now', 'localtime'))
+             WHERE date(scheduled_date) IS NOT scheduled_date",
+            [],
+        )?;
+
+        // SQLite can't read times such as "9:00" or "9:00 AM", so these are
+        // parsed here
+        let legacy_times = conn
+            .prepare(
+                "SELECT id, scheduled_time FROM tasks
+                 WHERE strftime('%H:%M', scheduled_time) IS NOT scheduled_time",
+            )?
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<_>>>()?;
+        for (id, time) in legacy_times {
+            conn.execute(
+                "UPDATE tasks SET scheduled_time = ? WHERE id = ?",
+                [legacy_time(&time), Some(id)],
+            )?;
+        }
+
+        conn.execute(
+            "UPDATE tasks SET end_date = date(end_date) WHERE date(end_date) IS NOT end_date",
+            [],
+        )?;
+
+        conn.execute(
+            "UPDATE tasks SET end_date = NULL WHERE end_date < scheduled_date",
+            [],
+        )?;
+
+        conn.execute(
+            "UPDATE tasks SET priority = 'medium'
+             WHERE priority IS NULL OR priority NOT IN ('high', 'medium', 'low')",
+            [],
+        )?;
+
+        conn.execute(
+            "UPDATE tasks SET color = lower(color) WHERE color != lower(color)",
+            [],
+        )?;
+
+        conn.execute(
+            "UPDATE tasks SET color = NULL
+             WHERE color IS NOT NULL
+               AND color NOT GLOB '#[0-9a-f][0-9a-f][0-9a-f]'
+               AND color NOT GLOB '#[0-9a-f][0-9a-f][0-9a-f][0-9a-f][0-9a-f][0-9a-f]'",
+            [],
+        )?;
+
+        // Goal slots are keyed by date, so a normalized date that is already
+        // taken is left as it was rather than overwriting anything
+        conn.execute(
+            "UPDATE OR IGNORE daily_goals SET goal_date = date(goal_date)
+             WHERE date(goal_date) IS NOT NULL AND date(goal_date) != goal_date",
+            [],
+        )?;
+
+        conn.execute(
+            "UPDATE OR IGNORE journal_entries SET entry_date = date(entry_date)
+             WHERE date(entry_date) IS NOT NULL AND date(entry_date) != entry_date",
+            [],
+        )?;
+
         // Create index for faster date-based queries
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_tasks_date ON tasks(scheduled_date)",
@@ -98,3 +560,19 @@ impl Database {
         Ok(())
     }
 }
+
+/// Reads a time saved before input was validated, such as `9:00`,
+/// `09:00:00`, `9:00 AM` or `9pm`, as `HH:MM`.
+fn legacy_time(value: &str) -> Option<String> {
+    let mut value = value.trim().to_uppercase();
+    if !value.contains(':') {
+        let hour_end = value
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(value.len());
+        value.insert_str(hour_end, ":00");
+    }
+    ["%H:%M", "%H:%M:%S", "%I:%M %p", "%I:%M%p", "%I:%M:%S %p"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(&value, format).ok())
+        .map(|time| time.format("%H:%M").to_string())
+}
@@ -0,0 +1,106 @@
+//! Databases and values shared by the tests. The factories fill in only
+//! what the table requires; tests set the rest with struct update syntax.
+
+use crate::database::{
+    CreateTaskInput, Database, HabitInput, HabitSchedule, Task, UpdateTaskInput, UpsertGoalInput,
+    UpsertPeriodGoalInput,
+};
+use rusqlite::Connection;
+
+/// A fresh in-memory database with every migration applied.
+pub(crate) fn connection() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    Database::run_migrations(&conn).unwrap();
+    conn
+}
+
+pub(crate) fn task_input(title: &str, date: &str) -> CreateTaskInput {
+    CreateTaskInput {
+        title: title.to_string(),
+        description: None,
+        scheduled_date: date.parse().unwrap(),
+        scheduled_time: None,
+        duration_minutes: None,
+        priority: None,
+        reminder_minutes: None,
+        color: None,
+        end_date: None,
+        is_all_day: None,
+        time_zone: None,
+    }
+}
+
+/// An update that sets nothing.
+pub(crate) fn no_changes(id: &str) -> UpdateTaskInput {
+    UpdateTaskInput {
+        id: id.to_string(),
+        title: None,
+        description: None,
+        scheduled_date: None,
+        scheduled_time: None,
+        duration_minutes: None,
+        priority: None,
+        is_completed: None,
+        reminder_minutes: None,
+        color: None,
+        end_date: None,
+        is_all_day: None,
+        time_zone: None,
+        clear_scheduled_time: false,
+        clear_end_date: false,
+    }
+}
+
+pub(crate) fn goal_input(date: &str, order: i32, text: &str) -> UpsertGoalInput {
+    UpsertGoalInput {
+        goal_date: date.parse().unwrap(),
+        goal_text: text.to_string(),
+        goal_order: order,
+        parent_goal_id: None,
+    }
+}
+
+pub(crate) fn period_goal_input(key: &str, text: &str) -> UpsertPeriodGoalInput {
+    UpsertPeriodGoalInput {
+        period_key: key.parse().unwrap(),
+        goal_text: text.to_string(),
+        goal_order: 1,
+    }
+}
+
+pub(crate) fn habit_input(schedule: HabitSchedule, target_value: Option<f64>) -> HabitInput {
+    HabitInput {
+        name: "Read".to_string(),
+        description: None,
+        schedule,
+        target_value,
+        unit: target_value.map(|_| "pages".to_string()),
+        reminder_time: None,
+        color: None,
+    }
+}
+
+/// An open, untimed half-hour task on 2026-03-14, as read back from the
+/// database, for code that works on tasks without storing them.
+pub(crate) fn task() -> Task {
+    Task {
+        id: "t".to_string(),
+        title: "Task".to_string(),
+        description: None,
+        scheduled_date: "2026-03-14".to_string(),
+        scheduled_time: None,
+        time_zone: None,
+        duration_minutes: 30,
+        priority: "medium".to_string(),
+        is_completed: false,
+        reminder_minutes: None,
+        color: None,
+        created_at: "2026-03-01 09:30:00".to_string(),
+        actual_minutes: 0,
+        is_blocked: false,
+        end_date: None,
+        is_all_day: false,
+        completed_at: None,
+        occurrence_date: "2026-03-14".to_string(),
+    }
+}
@@ -1,5 +1,9 @@
 pub mod connection;
+#[cfg(test)]
+pub(crate) mod fixtures;
 pub mod models;
+pub mod repositories;
 
-pub use connection::Database;
+pub use connection::{CancelToken, Database};
 pub use models::*;
+pub use repositories::*;
@@ -1,4 +1,289 @@
-use serde::{Deserialize, Serialize};
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveTime, Timelike, Weekday};
+use rusqlite::types::{ToSql, ToSqlOutput};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// A calendar date, exchanged as `YYYY-MM-DD`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date(pub NaiveDate);
+
+impl FromStr for Date {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, String> {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map(Date)
+            .map_err(|_| format!("invalid date \"{}\", expected YYYY-MM-DD", value))
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0.format("%Y-%m-%d"))
+    }
+}
+
+/// A time of day, exchanged as `HH:MM`. Seconds are accepted and dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time(pub NaiveTime);
+
+impl FromStr for Time {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, String> {
+        NaiveTime::parse_from_str(value, "%H:%M")
+            .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M:%S"))
+            .map(|time| Time(time.with_second(0).unwrap_or(time)))
+            .map_err(|_| format!("invalid time \"{}\", expected HH:MM", value))
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0.format("%H:%M"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    High,
+    #[default]
+    Medium,
+    Low,
+}
+
+impl Priority {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Priority::High => "high",
+            Priority::Medium => "medium",
+            Priority::Low => "low",
+        }
+    }
+}
+
+impl FromStr for Priority {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, String> {
+        match value {
+            "high" => Ok(Priority::High),
+            "medium" => Ok(Priority::Medium),
+            "low" => Ok(Priority::Low),
+            _ => Err(format!(
+                "invalid priority \"{}\", expected high, medium or low",
+                value
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A `#rgb` or `#rrggbb` color, stored in lowercase.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Color(String);
+
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, String> {
+        let valid = value.strip_prefix('#').is_some_and(|hex| {
+            matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit())
+        });
+        if valid {
+            Ok(Color(value.to_ascii_lowercase()))
+        } else {
+            Err(format!(
+                "invalid color \"{}\", expected a hex color such as #3b82f6",
+                value
+            ))
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GoalPeriod {
+    Week,
+    Month,
+    Quarter,
+    Year,
+}
+
+impl GoalPeriod {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GoalPeriod::Week => "week",
+            GoalPeriod::Month => "month",
+            GoalPeriod::Quarter => "quarter",
+            GoalPeriod::Year => "year",
+        }
+    }
+}
+
+/// A week, month, quarter or year, exchanged as `2026-W07`, `2026-02`,
+/// `2026-Q1` or `2026`. Weeks are ISO weeks, starting on Monday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PeriodKey {
+    pub period: GoalPeriod,
+    pub start: NaiveDate,
+}
+
+impl PeriodKey {
+    /// The period of the given kind that `date` falls in.
+    pub fn containing(period: GoalPeriod, date: NaiveDate) -> Self {
+        let start = match period {
+            GoalPeriod::Week => {
+                date - Duration::days(date.weekday().num_days_from_monday() as i64)
+            }
+            GoalPeriod::Month => date.with_day(1).unwrap_or(date),
+            GoalPeriod::Quarter => {
+                NaiveDate::from_ymd_opt(date.year(), date.month0() / 3 * 3 + 1, 1).unwrap_or(date)
+            }
+            GoalPeriod::Year => date.with_ordinal(1).unwrap_or(date),
+        };
+        PeriodKey { period, start }
+    }
+
+    /// The last day of the period.
+    pub fn end(&self) -> NaiveDate {
+        let next = match self.period {
+            GoalPeriod::Week => Some(self.start + Duration::days(7)),
+            GoalPeriod::Month => self.start.checked_add_months(Months::new(1)),
+            GoalPeriod::Quarter => self.start.checked_add_months(Months::new(3)),
+            GoalPeriod::Year => self.start.checked_add_months(Months::new(12)),
+        };
+        next.map_or(NaiveDate::MAX, |next| next - Duration::days(1))
+    }
+}
+
+impl FromStr for PeriodKey {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, String> {
+        let invalid = || {
+            format!(
+                "invalid period \"{}\", expected YYYY-Www, YYYY-MM, YYYY-Qn or YYYY",
+                value
+            )
+        };
+        let number = |digits: &str, len: usize| {
+            (digits.len() == len && digits.chars().all(|c| c.is_ascii_digit()))
+                .then(|| digits.parse::<u32>().ok())
+                .flatten()
+                .ok_or_else(invalid)
+        };
+
+        let (year, rest) = value.split_at_checked(4).ok_or_else(invalid)?;
+        let year = number(year, 4)? as i32;
+
+        let (period, start) = match rest.strip_prefix('-') {
+            None if rest.is_empty() => (GoalPeriod::Year, NaiveDate::from_ymd_opt(year, 1, 1)),
+            Some(week) if week.starts_with('W') => (
+                GoalPeriod::Week,
+                NaiveDate::from_isoywd_opt(year, number(&week[1..], 2)?, Weekday::Mon),
+            ),
+            Some(quarter) if quarter.starts_with('Q') => {
+                let quarter = number(&quarter[1..], 1)?;
+                let start = (1..=4)
+                    .contains(&quarter)
+                    .then(|| NaiveDate::from_ymd_opt(year, quarter * 3 - 2, 1))
+                    .flatten();
+                (GoalPeriod::Quarter, start)
+            }
+            Some(month) => (
+                GoalPeriod::Month,
+                NaiveDate::from_ymd_opt(year, number(month, 2)?, 1),
+            ),
+            None => return Err(invalid()),
+        };
+
+        start
+            .map(|start| PeriodKey { period, start })
+            .ok_or_else(invalid)
+    }
+}
+
+impl fmt::Display for PeriodKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.period {
+            GoalPeriod::Week => {
+                let week = self.start.iso_week();
+                write!(f, "{:04}-W{:02}", week.year(), week.week())
+            }
+            GoalPeriod::Month => write!(f, "{}", self.start.format("%Y-%m")),
+            GoalPeriod::Quarter => {
+                write!(f, "{:04}-Q{}", self.start.year(), self.start.month0() / 3 + 1)
+            }
+            GoalPeriod::Year => write!(f, "{:04}", self.start.year()),
+        }
+    }
+}
+
+/// Strings in and out for the types above, so a malformed value is turned
+/// away while the command arguments are read, before any SQL runs.
+macro_rules! string_value {
+    ($($name:ty),*) => {$(
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer)?
+                    .parse()
+                    .map_err(de::Error::custom)
+            }
+        }
+
+        impl ToSql for $name {
+            fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+                Ok(ToSqlOutput::from(self.to_string()))
+            }
+        }
+    )*};
+}
+
+string_value!(Date, Time, Color, PeriodKey);
+
+impl ToSql for Priority {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl ToSql for Mood {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl ToSql for MetricKind {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl ToSql for GoalPeriod {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Task {
@@ -7,24 +292,43 @@ pub struct Task {
     pub description: Option<String>,
     pub scheduled_date: String,
     pub scheduled_time: Option<String>,
+    /// IANA zone `scheduled_time` is wall-clock time in. Only timed tasks
+    /// have one; `None` means the zone the app is running in.
+    pub time_zone: Option<String>,
     pub duration_minutes: i32,
     pub priority: String,
     pub is_completed: bool,
     pub reminder_minutes: Option<i32>,
     pub color: Option<String>,
     pub created_at: String,
+    /// Minutes actually tracked against the task with timers.
+    pub actual_minutes: i32,
+    /// Set while any task this one depends on is still open.
+    pub is_blocked: bool,
+    /// Last day of a multi-day task, inclusive.
+    pub end_date: Option<String>,
+    pub is_all_day: bool,
+    /// When the task was marked done, if it is done and that was recorded.
+    pub completed_at: Option<String>,
+    /// The day this copy is listed under in a range query. Later days of a
+    /// spanning task repeat the task with this set to that day.
+    pub occurrence_date: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateTaskInput {
     pub title: String,
     pub description: Option<String>,
-    pub scheduled_date: String,
-    pub scheduled_time: Option<String>,
+    pub scheduled_date: Date,
+    pub scheduled_time: Option<Time>,
     pub duration_minutes: Option<i32>,
-    pub priority: Option<String>,
+    pub priority: Option<Priority>,
     pub reminder_minutes: Option<i32>,
-    pub color: Option<String>,
+    pub color: Option<Color>,
+    pub end_date: Option<Date>,
+    pub is_all_day: Option<bool>,
+    /// Defaults to the zone the app is running in for timed tasks.
+    pub time_zone: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -32,15 +336,177 @@ pub struct UpdateTaskInput {
     pub id: String,
     pub title: Option<String>,
     pub description: Option<String>,
-    pub scheduled_date: Option<String>,
-    pub scheduled_time: Option<String>,
+    pub scheduled_date: Option<Date>,
+    pub scheduled_time: Option<Time>,
     pub duration_minutes: Option<i32>,
-    pub priority: Option<String>,
+    pub priority: Option<Priority>,
     pub is_completed: Option<bool>,
     pub reminder_minutes: Option<i32>,
-    pub color: Option<String>,
+    pub color: Option<Color>,
+    pub end_date: Option<Date>,
+    pub is_all_day: Option<bool>,
+    pub time_zone: Option<String>,
     #[serde(default)]
     pub clear_scheduled_time: bool,
+    #[serde(default)]
+    pub clear_end_date: bool,
+}
+
+/// Two timed tasks whose scheduled time overlaps.
+#[derive(Debug, Serialize, Clone)]
+pub struct TaskConflict {
+    pub task_id: String,
+    pub other_task_id: String,
+    pub overlap_start: String,
+    pub overlap_end: String,
+    pub overlap_minutes: i32,
+}
+
+/// A task scheduled to start before one of its prerequisites ends.
+#[derive(Debug, Serialize, Clone)]
+pub struct DependencyWarning {
+    pub task_id: String,
+    pub prerequisite_id: String,
+    pub message: String,
+}
+
+/// A saved task together with any tasks it now overlaps and any
+/// prerequisites it is scheduled ahead of.
+#[derive(Debug, Serialize, Clone)]
+pub struct TaskWithConflicts {
+    #[serde(flatten)]
+    pub task: Task,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<TaskConflict>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dependency_warnings: Vec<DependencyWarning>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TaskDependencies {
+    pub prerequisites: Vec<Task>,
+    pub dependents: Vec<Task>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScheduleBreak {
+    pub start: Time,
+    pub end: Time,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutoScheduleInput {
+    pub date: Date,
+    pub work_start: Option<Time>,
+    pub work_end: Option<Time>,
+    pub buffer_minutes: Option<i32>,
+    #[serde(default)]
+    pub breaks: Vec<ScheduleBreak>,
+    /// Untimed tasks to place; defaults to every open untimed task that day.
+    pub task_ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlannedTask {
+    pub task_id: String,
+    pub title: String,
+    pub scheduled_time: Time,
+    pub duration_minutes: i32,
+    pub priority: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct UnplacedTask {
+    pub task_id: String,
+    pub title: String,
+    pub reason: String,
+}
+
+/// A proposed schedule for one day, returned for preview before applying.
+#[derive(Debug, Serialize, Clone)]
+pub struct SchedulePlan {
+    pub date: String,
+    pub planned: Vec<PlannedTask>,
+    pub unplaced: Vec<UnplacedTask>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApplyScheduleInput {
+    pub date: Date,
+    pub planned: Vec<PlannedTask>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TimeEntry {
+    pub id: String,
+    pub task_id: String,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub duration_seconds: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TaskTime {
+    pub task_id: String,
+    pub title: String,
+    pub estimated_minutes: i32,
+    pub actual_minutes: i32,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TimeReportPeriod {
+    /// The day, or the Monday starting the week.
+    pub period_start: String,
+    pub estimated_minutes: i32,
+    pub actual_minutes: i32,
+    pub tasks: Vec<TaskTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FocusSession {
+    pub id: String,
+    pub task_id: String,
+    pub work_minutes: i32,
+    pub short_break_minutes: i32,
+    pub long_break_minutes: i32,
+    pub long_break_every: i32,
+    /// `work`, `short_break` or `long_break`.
+    pub phase: String,
+    pub phase_started_at: String,
+    pub phase_ends_at: String,
+    pub completed_cycles: i32,
+    pub started_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StartFocusInput {
+    pub task_id: String,
+    pub work_minutes: Option<i32>,
+    pub short_break_minutes: Option<i32>,
+    pub long_break_minutes: Option<i32>,
+    pub long_break_every: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FocusPhaseChange {
+    pub previous_phase: String,
+    pub session: FocusSession,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FocusTaskStats {
+    pub task_id: String,
+    pub title: String,
+    pub cycles: i32,
+    pub focus_minutes: i32,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FocusDayStats {
+    pub date: String,
+    pub cycles: i32,
+    pub focus_minutes: i32,
+    pub tasks: Vec<FocusTaskStats>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -50,26 +516,538 @@ pub struct DailyGoal {
     pub goal_text: String,
     pub goal_order: i32,
     pub is_completed: bool,
+    /// The week, month, quarter or year goal this day's goal works towards
+    pub parent_goal_id: Option<String>,
+    /// For a goal carried over from an earlier day, the goal it started as
+    pub original_goal_id: Option<String>,
+    /// The day a carried-over goal was first set
+    pub carried_since: Option<String>,
+    /// Completed and reopened along with its linked tasks
+    pub auto_complete: bool,
+    pub linked_tasks: i32,
+    pub completed_linked_tasks: i32,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpsertGoalInput {
-    pub goal_date: String,
+    pub goal_date: Date,
     pub goal_text: String,
     pub goal_order: i32,
+    /// Links the goal to a period goal; leaving it out keeps any existing link
+    #[serde(default)]
+    pub parent_goal_id: Option<String>,
+}
+
+/// A goal added among the day's others rather than into a fixed slot.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InsertGoalInput {
+    pub goal_date: Date,
+    pub goal_text: String,
+    /// 1 for the top; goals from there on move down one. Left out, the goal
+    /// goes last.
+    pub position: Option<i32>,
+    #[serde(default)]
+    pub parent_goal_id: Option<String>,
+}
+
+/// A goal for a whole week, month, quarter or year.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PeriodGoal {
+    pub id: String,
+    pub period: String,
+    pub period_key: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub goal_text: String,
+    pub goal_order: i32,
+    pub is_completed: bool,
+    pub child_goals: i32,
+    pub completed_child_goals: i32,
+    /// Share of linked daily goals completed; without any, 1 or 0 by the
+    /// goal's own completion
+    pub progress: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpsertPeriodGoalInput {
+    pub period_key: PeriodKey,
+    pub goal_text: String,
+    pub goal_order: i32,
+}
+
+/// Goal counts over some set of days; only days that have goals count.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct GoalTotals {
+    pub total_goals: i32,
+    pub completed_goals: i32,
+    pub completion_rate: f64,
+    pub days_with_goals: i32,
+    /// Days on which every goal was completed
+    pub perfect_days: i32,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GoalPeriodStats {
+    /// Monday of the week, or the first of the month
+    pub period_start: String,
+    #[serde(flatten)]
+    pub totals: GoalTotals,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GoalWeekdayStats {
+    pub weekday: String,
+    #[serde(flatten)]
+    pub totals: GoalTotals,
+}
+
+/// A run of consecutive days on which every goal was completed.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct GoalStreak {
+    pub days: i32,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GoalStats {
+    pub start_date: String,
+    pub end_date: String,
+    #[serde(flatten)]
+    pub totals: GoalTotals,
+    pub current_streak: GoalStreak,
+    pub longest_streak: GoalStreak,
+    pub weeks: Vec<GoalPeriodStats>,
+    pub months: Vec<GoalPeriodStats>,
+    pub weekdays: Vec<GoalWeekdayStats>,
+}
+
+/// How often a habit is meant to be done. Weekdays are exchanged as
+/// `Mon` … `Sun`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HabitSchedule {
+    Daily,
+    /// Only on these days of the week
+    Weekdays { days: Vec<Weekday> },
+    /// On any days, as long as the week has this many
+    PerWeek { times: i32 },
+}
+
+impl HabitSchedule {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HabitSchedule::Daily => "daily",
+            HabitSchedule::Weekdays { .. } => "weekdays",
+            HabitSchedule::PerWeek { .. } => "per_week",
+        }
+    }
+
+    /// Whether the habit is due on `date`. A per-week habit can be done on
+    /// any day, so every day counts.
+    pub fn is_due(&self, date: NaiveDate) -> bool {
+        match self {
+            HabitSchedule::Weekdays { days } => days.contains(&date.weekday()),
+            HabitSchedule::Daily | HabitSchedule::PerWeek { .. } => true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Habit {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub schedule: HabitSchedule,
+    /// Amount a check-in needs to count as done, in `unit`; without one any
+    /// check-in does.
+    pub target_value: Option<f64>,
+    pub unit: Option<String>,
+    /// Local time to be reminded on due days not yet checked in
+    pub reminder_time: Option<String>,
+    pub color: Option<String>,
+    pub is_archived: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HabitInput {
+    pub name: String,
+    pub description: Option<String>,
+    pub schedule: HabitSchedule,
+    pub target_value: Option<f64>,
+    pub unit: Option<String>,
+    pub reminder_time: Option<Time>,
+    pub color: Option<Color>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HabitCheckIn {
+    pub habit_id: String,
+    pub checkin_date: String,
+    pub value: Option<f64>,
+    /// Whether the value reaches the habit's target
+    pub is_complete: bool,
+    pub created_at: String,
+}
+
+/// A habit as it stands on one day.
+#[derive(Debug, Serialize, Clone)]
+pub struct HabitStatus {
+    #[serde(flatten)]
+    pub habit: Habit,
+    pub is_due: bool,
+    pub check_in: Option<HabitCheckIn>,
+    /// Completed days so far in the day's week, for per-week habits
+    pub week_completed: i32,
+}
+
+/// A run of completed due days, or of weeks that met a per-week habit's
+/// count.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct HabitStreak {
+    pub length: i32,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+}
+
+/// One heatmap cell.
+#[derive(Debug, Serialize, Clone)]
+pub struct HabitDay {
+    pub date: String,
+    pub is_due: bool,
+    pub value: Option<f64>,
+    pub is_complete: bool,
+    /// Share of the target reached, capped at 1
+    pub level: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct HabitStats {
+    pub habit_id: String,
+    pub start_date: String,
+    pub end_date: String,
+    /// `days`, or `weeks` for per-week habits
+    pub streak_unit: String,
+    pub current_streak: HabitStreak,
+    pub longest_streak: HabitStreak,
+    pub due_days: i32,
+    pub completed_days: i32,
+    pub completion_rate: f64,
+    pub days: Vec<HabitDay>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mood {
+    Great,
+    Good,
+    Okay,
+    Bad,
+    Terrible,
+}
+
+impl Mood {
+    /// Best first.
+    pub const ALL: [Mood; 5] = [
+        Mood::Great,
+        Mood::Good,
+        Mood::Okay,
+        Mood::Bad,
+        Mood::Terrible,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Mood::Great => "great",
+            Mood::Good => "good",
+            Mood::Okay => "okay",
+            Mood::Bad => "bad",
+            Mood::Terrible => "terrible",
+        }
+    }
+
+    /// 5 for great down to 1 for terrible, for averaging.
+    pub fn score(self) -> f64 {
+        match self {
+            Mood::Great => 5.0,
+            Mood::Good => 4.0,
+            Mood::Okay => 3.0,
+            Mood::Bad => 2.0,
+            Mood::Terrible => 1.0,
+        }
+    }
+}
+
+impl FromStr for Mood {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, String> {
+        Mood::ALL
+            .into_iter()
+            .find(|mood| mood.as_str() == value)
+            .ok_or_else(|| {
+                format!(
+                    "invalid mood \"{}\", expected great, good, okay, bad or terrible",
+                    value
+                )
+            })
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct JournalEntry {
     pub id: String,
     pub entry_date: String,
+    pub title: Option<String>,
     pub content: String,
     pub mood: Option<String>,
+    /// RFC 3339, in UTC. Entries from before a day could hold several
+    /// have no times.
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// A day with a mood next to how the day went.
+#[derive(Debug, Serialize, Clone)]
+pub struct MoodDay {
+    pub date: String,
+    pub mood: Mood,
+    pub score: f64,
+    /// Share of the day's scheduled tasks completed, if it had any
+    pub task_completion_rate: Option<f64>,
+    /// Share of the day's goals completed, if it had any
+    pub goal_completion_rate: Option<f64>,
+    pub tracked_minutes: i32,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MoodCount {
+    pub mood: Mood,
+    pub days: i32,
+    pub share: f64,
+    /// Averages over the days with this mood
+    pub task_completion_rate: Option<f64>,
+    pub goal_completion_rate: Option<f64>,
+    pub tracked_minutes: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MoodWeekday {
+    pub weekday: String,
+    pub days: i32,
+    pub average_score: Option<f64>,
+}
+
+/// Pearson correlation of the mood score with each measure, over the days
+/// that have both. Left out with fewer than three such days or when either
+/// side never varies.
+#[derive(Debug, Serialize, Clone)]
+pub struct MoodCorrelations {
+    pub task_completion: Option<f64>,
+    pub goal_completion: Option<f64>,
+    pub tracked_minutes: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MoodReport {
+    pub start_date: String,
+    pub end_date: String,
+    pub days_with_mood: i32,
+    pub average_score: Option<f64>,
+    /// Every mood, best first
+    pub distribution: Vec<MoodCount>,
+    pub series: Vec<MoodDay>,
+    /// Monday first
+    pub weekdays: Vec<MoodWeekday>,
+    pub correlations: MoodCorrelations,
+}
+
+/// How someone felt at one moment; a day can have several.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WellbeingCheckIn {
+    pub id: String,
+    pub entry_date: String,
+    /// RFC 3339, in UTC
+    pub recorded_at: String,
+    pub mood: Option<Mood>,
+    /// 1 (drained) to 5 (energetic)
+    pub energy: Option<i32>,
+    /// 1 (calm) to 5 (overwhelmed)
+    pub stress: Option<i32>,
+    pub note: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct UpsertEntryInput {
+pub struct WellbeingCheckInInput {
+    pub entry_date: Date,
+    /// RFC 3339; defaults to now
+    pub recorded_at: Option<String>,
+    pub mood: Option<Mood>,
+    pub energy: Option<i32>,
+    pub stress: Option<i32>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricKind {
+    Number,
+    /// Stored as 1 for yes and 0 for no
+    Boolean,
+}
+
+impl MetricKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MetricKind::Number => "number",
+            MetricKind::Boolean => "boolean",
+        }
+    }
+}
+
+/// Something the user tracks daily beyond the built-in fields, such as
+/// caffeine or whether they meditated.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WellbeingMetric {
+    pub id: String,
+    pub name: String,
+    pub kind: MetricKind,
+    pub unit: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WellbeingMetricInput {
+    pub name: String,
+    pub kind: MetricKind,
+    pub unit: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MetricValue {
+    pub metric_id: String,
     pub entry_date: String,
+    pub value: f64,
+}
+
+/// Everything recorded about one day's wellbeing.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct WellbeingDay {
+    pub date: String,
+    /// Earliest first
+    pub check_ins: Vec<WellbeingCheckIn>,
+    pub sleep_hours: Option<f64>,
+    pub metrics: Vec<MetricValue>,
+    /// Averages over the day's check-ins, moods scored 5 for great to 1
+    pub average_mood: Option<f64>,
+    pub average_energy: Option<f64>,
+    pub average_stress: Option<f64>,
+}
+
+/// Saves the day's entry: the first one written that day.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpsertEntryInput {
+    pub entry_date: Date,
     pub content: String,
-    pub mood: Option<String>,
+    pub mood: Option<Mood>,
+}
+
+/// Adds another entry to the day.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateEntryInput {
+    pub entry_date: Date,
+    pub title: Option<String>,
+    pub content: String,
+    pub mood: Option<Mood>,
+    /// RFC 3339; defaults to now
+    pub created_at: Option<String>,
+}
+
+/// Replaces an entry's title, content and mood.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateEntryInput {
+    pub id: String,
+    pub title: Option<String>,
+    pub content: String,
+    pub mood: Option<Mood>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json<T: for<'de> Deserialize<'de>>(value: &str) -> Result<T, serde_json::Error> {
+        serde_json::from_str(value)
+    }
+
+    #[test]
+    fn dates_must_exist_on_the_calendar() {
+        assert_eq!("2024-02-29".parse::<Date>().unwrap().to_string(), "2024-02-29");
+        assert!("2024-02-30".parse::<Date>().is_err());
+        assert!("2023-02-29".parse::<Date>().is_err());
+        assert!("2024-13-01".parse::<Date>().is_err());
+        assert!("2024-02-01T09:00".parse::<Date>().is_err());
+
+        assert_eq!(json::<Date>("\"2024-02-29\"").unwrap().to_string(), "2024-02-29");
+        assert!(json::<Date>("\"2024-02-30\"").is_err());
+        assert!(json::<Date>("20240229").is_err());
+    }
+
+    #[test]
+    fn times_are_hours_and_minutes() {
+        assert_eq!("09:05".parse::<Time>().unwrap().to_string(), "09:05");
+        assert_eq!("23:59:30".parse::<Time>().unwrap().to_string(), "23:59");
+        assert!("25:00".parse::<Time>().is_err());
+        assert!("12:60".parse::<Time>().is_err());
+        assert!("9am".parse::<Time>().is_err());
+        assert!("".parse::<Time>().is_err());
+
+        assert_eq!(json::<Time>("\"00:00\"").unwrap().to_string(), "00:00");
+        assert!(json::<Time>("\"25:00\"").is_err());
+    }
+
+    #[test]
+    fn colors_are_short_or_long_hex() {
+        assert_eq!("#ABC".parse::<Color>().unwrap().to_string(), "#abc");
+        assert_eq!("#3B82F6".parse::<Color>().unwrap().to_string(), "#3b82f6");
+        assert!("#12345g".parse::<Color>().is_err());
+        assert!("#1234".parse::<Color>().is_err());
+        assert!("3b82f6".parse::<Color>().is_err());
+        assert!("red".parse::<Color>().is_err());
+
+        assert_eq!(json::<Color>("\"#FFF\"").unwrap().to_string(), "#fff");
+        assert!(json::<Color>("\"#12345g\"").is_err());
+    }
+
+    #[test]
+    fn priorities_are_one_of_three() {
+        assert_eq!("high".parse::<Priority>(), Ok(Priority::High));
+        assert_eq!("medium".parse::<Priority>(), Ok(Priority::Medium));
+        assert_eq!("low".parse::<Priority>(), Ok(Priority::Low));
+        assert!("urgent".parse::<Priority>().is_err());
+        assert!("High".parse::<Priority>().is_err());
+
+        assert_eq!(json::<Priority>("\"low\"").unwrap(), Priority::Low);
+        assert!(json::<Priority>("\"urgent\"").is_err());
+    }
+
+    #[test]
+    fn entry_moods_are_one_of_five() {
+        let input: UpsertEntryInput =
+            json(r#"{"entry_date": "2024-02-29", "content": "", "mood": "good"}"#).unwrap();
+        assert_eq!(input.mood, Some(Mood::Good));
+
+        let input: CreateEntryInput = json(
+            r#"{"entry_date": "2024-02-29", "title": null, "content": "", "mood": null, "created_at": null}"#,
+        )
+        .unwrap();
+        assert_eq!(input.mood, None);
+
+        assert!(json::<UpsertEntryInput>(
+            r#"{"entry_date": "2024-02-29", "content": "", "mood": "meh"}"#
+        )
+        .is_err());
+        assert!("meh".parse::<Mood>().is_err());
+    }
 }
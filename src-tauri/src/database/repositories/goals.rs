@@ -0,0 +1,631 @@
+use crate::database::{DailyGoal, GoalStreak, GoalTotals, UpsertGoalInput};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use uuid::Uuid;
+
+pub(crate) const GOAL_COLUMNS: &str = "id, goal_date, goal_text, goal_order, is_completed,
+     parent_goal_id, original_goal_id, carried_since, auto_complete,
+     (SELECT COUNT(*) FROM goal_tasks l WHERE l.goal_id = daily_goals.id),
+     (SELECT COUNT(*) FROM goal_tasks l JOIN tasks t ON t.id = l.task_id
+      WHERE l.goal_id = daily_goals.id AND t.is_completed = 1)";
+
+pub(crate) fn goal_from_row(row: &Row) -> rusqlite::Result<DailyGoal> {
+    Ok(DailyGoal {
+        id: row.get(0)?,
+        goal_date: row.get(1)?,
+        goal_text: row.get(2)?,
+        goal_order: row.get(3)?,
+        is_completed: row.get::<_, i32>(4)? != 0,
+        parent_goal_id: row.get(5)?,
+        original_goal_id: row.get(6)?,
+        carried_since: row.get(7)?,
+        auto_complete: row.get::<_, Option<i32>>(8)?.unwrap_or(0) != 0,
+        linked_tasks: row.get(9)?,
+        completed_linked_tasks: row.get(10)?,
+    })
+}
+
+/// Per-day goal counts between ?1 and ?2, the base of every statistic.
+const GOAL_DAYS: &str = "WITH days AS (
+         SELECT goal_date, COUNT(*) AS total, SUM(is_completed) AS completed
+         FROM daily_goals
+         WHERE goal_date BETWEEN ?1 AND ?2
+         GROUP BY goal_date
+     )";
+
+/// Runs of perfect days: consecutive dates minus their row number stay
+/// constant within a run, so that difference identifies it.
+const PERFECT_RUNS: &str = "perfect AS (
+         SELECT goal_date,
+                julianday(goal_date) - ROW_NUMBER() OVER (ORDER BY goal_date) AS run
+         FROM days
+         WHERE completed = total
+     ),
+     runs AS (
+         SELECT COUNT(*) AS days, MIN(goal_date) AS start_date, MAX(goal_date) AS end_date
+         FROM perfect
+         GROUP BY run
+     )";
+
+pub struct GoalRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> GoalRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        GoalRepository { conn }
+    }
+
+    pub fn get(&self, id: &str) -> rusqlite::Result<DailyGoal> {
+        self.conn.query_row(
+            &format!("SELECT {} FROM daily_goals WHERE id = ?", GOAL_COLUMNS),
+            params![id],
+            goal_from_row,
+        )
+    }
+
+    pub fn for_date(&self, date: &str) -> rusqlite::Result<Vec<DailyGoal>> {
+        self.conn
+            .prepare(&format!(
+                "SELECT {} FROM daily_goals WHERE goal_date = ? ORDER BY goal_order",
+                GOAL_COLUMNS
+            ))?
+            .query_map(params![date], goal_from_row)?
+            .collect()
+    }
+
+    /// Goals between the two dates, with either end left open.
+    pub fn between(
+        &self,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+    ) -> rusqlite::Result<Vec<DailyGoal>> {
+        self.conn
+            .prepare(&format!(
+                "SELECT {} FROM daily_goals
+                 WHERE (?1 IS NULL OR goal_date >= ?1) AND (?2 IS NULL OR goal_date <= ?2)
+                 ORDER BY goal_date, goal_order",
+                GOAL_COLUMNS
+            ))?
+            .query_map(params![start_date, end_date], goal_from_row)?
+            .collect()
+    }
+
+    /// The goal in a given slot of the day, if there is one.
+    pub fn id_at(&self, date: &str, order: i32) -> rusqlite::Result<Option<String>> {
+        self.conn
+            .query_row(
+                "SELECT id FROM daily_goals WHERE goal_date = ? AND goal_order = ?",
+                params![date, order],
+                |row| row.get(0),
+            )
+            .optional()
+    }
+
+    pub fn insert(&self, input: &UpsertGoalInput) -> rusqlite::Result<String> {
+        let id = Uuid::new_v4().to_string();
+        self.conn.execute(
+            "INSERT INTO daily_goals (id, goal_date, goal_text, goal_order, parent_goal_id)
+             VALUES (?, ?, ?, ?, ?)",
+            params![
+                id,
+                input.goal_date,
+                input.goal_text,
+                input.goal_order,
+                input.parent_goal_id
+            ],
+        )?;
+        Ok(id)
+    }
+
+    /// Copies `goal` onto `date` in slot `order`, pointing back at the goal
+    /// it started as.
+    pub fn insert_carried(
+        &self,
+        goal: &DailyGoal,
+        date: &str,
+        order: i32,
+        parent_goal_id: Option<&str>,
+    ) -> rusqlite::Result<String> {
+        let id = Uuid::new_v4().to_string();
+        self.conn.execute(
+            "INSERT INTO daily_goals (id, goal_date, goal_text, goal_order, parent_goal_id,
+                                      original_goal_id, carried_since, auto_complete)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                id,
+                date,
+                goal.goal_text,
+                order,
+                parent_goal_id,
+                goal.original_goal_id.as_deref().unwrap_or(&goal.id),
+                goal.carried_since.as_deref().unwrap_or(&goal.goal_date),
+                goal.auto_complete
+            ],
+        )?;
+        // The copy tracks the same tasks
+        self.conn.execute(
+            "INSERT INTO goal_tasks (goal_id, task_id)
+             SELECT ?, task_id FROM goal_tasks WHERE goal_id = ?",
+            params![id, goal.id],
+        )?;
+        Ok(id)
+    }
+
+    /// Whether `date` already has a copy of the goal, or is where it started.
+    pub fn has_lineage_on(&self, goal: &DailyGoal, date: &str) -> rusqlite::Result<bool> {
+        let original = goal.original_goal_id.as_deref().unwrap_or(&goal.id);
+        self.conn.query_row(
+            "SELECT EXISTS(
+                 SELECT 1 FROM daily_goals
+                 WHERE goal_date = ?1 AND (id = ?2 OR original_goal_id = ?2)
+             )",
+            params![date, original],
+            |row| row.get(0),
+        )
+    }
+
+    pub fn set_text(&self, id: &str, text: &str) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE daily_goals SET goal_text = ? WHERE id = ?",
+            params![text, id],
+        )?;
+        Ok(())
+    }
+
+    /// Moves goals of the day into new slots. Each is parked on a negative
+    /// slot first, so no update lands on a slot another goal still holds;
+    /// run it in a transaction so nobody sees the parked slots.
+    pub fn set_orders(&self, date: &str, orders: &[(&str, i32)]) -> rusqlite::Result<()> {
+        let mut stmt = self
+            .conn
+            .prepare("UPDATE daily_goals SET goal_order = ? WHERE id = ? AND goal_date = ?")?;
+        for (parked, (id, _)) in orders.iter().enumerate() {
+            stmt.execute(params![-(parked as i32) - 1, id, date])?;
+        }
+        for (id, order) in orders {
+            stmt.execute(params![order, id, date])?;
+        }
+        Ok(())
+    }
+
+    pub fn set_parent(&self, id: &str, parent_goal_id: Option<&str>) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE daily_goals SET parent_goal_id = ? WHERE id = ?",
+            params![parent_goal_id, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn toggle_completion(&self, id: &str) -> rusqlite::Result<DailyGoal> {
+        self.conn.execute(
+            "UPDATE daily_goals SET is_completed = NOT is_completed WHERE id = ?",
+            params![id],
+        )?;
+        self.get(id)
+    }
+
+    pub fn delete(&self, id: &str) -> rusqlite::Result<()> {
+        self.conn
+            .execute("DELETE FROM goal_tasks WHERE goal_id = ?", params![id])?;
+        self.conn
+            .execute("DELETE FROM daily_goals WHERE id = ?", params![id])?;
+        Ok(())
+    }
+
+    pub fn link_task(&self, id: &str, task_id: &str) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO goal_tasks (goal_id, task_id) VALUES (?, ?)",
+            params![id, task_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn unlink_task(&self, id: &str, task_id: &str) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "DELETE FROM goal_tasks WHERE goal_id = ? AND task_id = ?",
+            params![id, task_id],
+        )?;
+        Ok(())
+    }
+
+    /// Goals the task is linked to.
+    pub fn linked_to_task(&self, task_id: &str) -> rusqlite::Result<Vec<String>> {
+        self.conn
+            .prepare("SELECT goal_id FROM goal_tasks WHERE task_id = ?")?
+            .query_map(params![task_id], |row| row.get(0))?
+            .collect()
+    }
+
+    pub fn set_auto_complete(&self, id: &str, auto_complete: bool) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE daily_goals SET auto_complete = ? WHERE id = ?",
+            params![auto_complete, id],
+        )?;
+        Ok(())
+    }
+
+    /// Completes an auto-completing goal once all its linked tasks are done,
+    /// and reopens it when one isn't. Returns whether the goal changed.
+    pub fn sync_with_tasks(&self, id: &str) -> rusqlite::Result<bool> {
+        let changed = self.conn.execute(
+            "UPDATE daily_goals
+             SET is_completed = NOT is_completed
+             WHERE id = ?1
+               AND auto_complete = 1
+               AND EXISTS(SELECT 1 FROM goal_tasks WHERE goal_id = ?1)
+               AND is_completed != (
+                   SELECT MIN(COALESCE(t.is_completed, 0)) FROM goal_tasks l
+                   JOIN tasks t ON t.id = l.task_id
+                   WHERE l.goal_id = ?1
+               )",
+            params![id],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Goal totals between the two dates, grouped by the SQL expression
+    /// `key` over `goal_date`, in key order.
+    fn totals_by(
+        &self,
+        key: &str,
+        start_date: &str,
+        end_date: &str,
+    ) -> rusqlite::Result<Vec<(String, GoalTotals)>> {
+        self.conn
+            .prepare(&format!(
+                "{}
+                 SELECT {} AS period, SUM(total), SUM(completed),
+                        CAST(SUM(completed) AS REAL) / SUM(total),
+                        COUNT(*), SUM(completed = total)
+                 FROM days
+                 GROUP BY period
+                 ORDER BY period",
+                GOAL_DAYS, key
+            ))?
+            .query_map(params![start_date, end_date], |row| {
+                Ok((
+                    row.get(0)?,
+                    GoalTotals {
+                        total_goals: row.get(1)?,
+                        completed_goals: row.get(2)?,
+                        completion_rate: row.get(3)?,
+                        days_with_goals: row.get(4)?,
+                        perfect_days: row.get(5)?,
+                    },
+                ))
+            })?
+            .collect()
+    }
+
+    pub fn totals(&self, start_date: &str, end_date: &str) -> rusqlite::Result<GoalTotals> {
+        Ok(self
+            .totals_by("''", start_date, end_date)?
+            .pop()
+            .map(|(_, totals)| totals)
+            .unwrap_or_default())
+    }
+
+    /// Totals per day with goals.
+    pub fn daily_totals(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> rusqlite::Result<Vec<(String, GoalTotals)>> {
+        self.totals_by("goal_date", start_date, end_date)
+    }
+
+    /// Totals per week, keyed by the week's Monday.
+    pub fn weekly_totals(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> rusqlite::Result<Vec<(String, GoalTotals)>> {
+        self.totals_by(
+            "date(goal_date, 'weekday 0', '-6 days')",
+            start_date,
+            end_date,
+        )
+    }
+
+    /// Totals per calendar month, keyed by its first day.
+    pub fn monthly_totals(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> rusqlite::Result<Vec<(String, GoalTotals)>> {
+        self.totals_by("date(goal_date, 'start of month')", start_date, end_date)
+    }
+
+    /// Totals per day of the week, keyed by SQLite's weekday number
+    /// ("0" is Sunday).
+    pub fn weekday_totals(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> rusqlite::Result<Vec<(String, GoalTotals)>> {
+        self.totals_by("strftime('%w', goal_date)", start_date, end_date)
+    }
+
+    fn perfect_run(
+        &self,
+        start_date: &str,
+        end_date: &str,
+        pick: &str,
+    ) -> rusqlite::Result<Option<GoalStreak>> {
+        self.conn
+            .query_row(
+                &format!(
+                    "{}, {} SELECT days, start_date, end_date FROM runs {} LIMIT 1",
+                    GOAL_DAYS, PERFECT_RUNS, pick
+                ),
+                params![start_date, end_date],
+                |row| {
+                    Ok(GoalStreak {
+                        days: row.get(0)?,
+                        start_date: row.get(1)?,
+                        end_date: row.get(2)?,
+                    })
+                },
+            )
+            .optional()
+    }
+
+    /// The longest run of perfect days between the two dates; the earliest
+    /// one on a tie.
+    pub fn longest_streak(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> rusqlite::Result<Option<GoalStreak>> {
+        self.perfect_run(start_date, end_date, "ORDER BY days DESC, start_date")
+    }
+
+    /// The run of perfect days still going on `date`. A day that isn't
+    /// finished yet doesn't break it, so a run ending the day before counts.
+    pub fn streak_through(&self, date: &str) -> rusqlite::Result<Option<GoalStreak>> {
+        self.perfect_run(
+            "",
+            date,
+            "WHERE end_date >= date(?2, '-1 day') ORDER BY end_date DESC",
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::fixtures::{connection, goal_input, task_input};
+    use crate::database::TaskRepository;
+
+    #[test]
+    fn goals_come_back_in_slot_order() {
+        let conn = connection();
+        let goals = GoalRepository::new(&conn);
+        goals
+            .insert(&goal_input("2026-05-01", 2, "Second"))
+            .unwrap();
+        goals.insert(&goal_input("2026-05-01", 1, "First")).unwrap();
+        goals
+            .insert(&goal_input("2026-05-02", 1, "Tomorrow"))
+            .unwrap();
+
+        let texts: Vec<String> = goals
+            .for_date("2026-05-01")
+            .unwrap()
+            .into_iter()
+            .map(|g| g.goal_text)
+            .collect();
+        assert_eq!(texts, ["First", "Second"]);
+        assert_eq!(goals.between(Some("2026-05-02"), None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn slot_lookup_and_edits() {
+        let conn = connection();
+        let goals = GoalRepository::new(&conn);
+        let id = goals.insert(&goal_input("2026-05-01", 1, "Run")).unwrap();
+
+        assert_eq!(goals.id_at("2026-05-01", 1).unwrap(), Some(id.clone()));
+        assert_eq!(goals.id_at("2026-05-01", 2).unwrap(), None);
+        assert!(goals.insert(&goal_input("2026-05-01", 1, "Taken")).is_err());
+
+        goals.set_text(&id, "Run 5k").unwrap();
+        assert!(goals.toggle_completion(&id).unwrap().is_completed);
+        assert_eq!(goals.get(&id).unwrap().goal_text, "Run 5k");
+
+        goals.delete(&id).unwrap();
+        assert!(goals.for_date("2026-05-01").unwrap().is_empty());
+    }
+
+    /// One goal per listed day, completed when marked with `true`.
+    fn record(goals: &GoalRepository, days: &[(&str, bool)]) {
+        for (date, done) in days {
+            let id = goals.insert(&goal_input(date, 1, "Goal")).unwrap();
+            if *done {
+                goals.toggle_completion(&id).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn auto_complete_follows_linked_tasks() {
+        let conn = connection();
+        let goals = GoalRepository::new(&conn);
+        let tasks = TaskRepository::new(&conn);
+        let task = |title: &str| {
+            tasks
+                .insert(&task_input(title, "2026-05-01"), None)
+                .unwrap()
+        };
+        let (first, second) = (task("Draft"), task("Send"));
+
+        let id = goals
+            .insert(&goal_input("2026-05-01", 1, "Ship it"))
+            .unwrap();
+        goals.link_task(&id, &first).unwrap();
+        goals.link_task(&id, &second).unwrap();
+        tasks.toggle_completion(&first).unwrap();
+
+        // Off by default
+        assert!(!goals.sync_with_tasks(&id).unwrap());
+        goals.set_auto_complete(&id, true).unwrap();
+        assert!(!goals.sync_with_tasks(&id).unwrap());
+        let progress = goals.get(&id).unwrap();
+        assert_eq!(
+            (progress.linked_tasks, progress.completed_linked_tasks),
+            (2, 1)
+        );
+
+        tasks.toggle_completion(&second).unwrap();
+        assert!(goals.sync_with_tasks(&id).unwrap());
+        assert!(goals.get(&id).unwrap().is_completed);
+
+        // Reverting a task reopens the goal
+        tasks.toggle_completion(&first).unwrap();
+        assert!(goals.sync_with_tasks(&id).unwrap());
+        assert!(!goals.get(&id).unwrap().is_completed);
+        assert_eq!(goals.linked_to_task(&first).unwrap(), [id.as_str()]);
+        assert_eq!(tasks.linked_to_goal(&id).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn orders_can_be_swapped_without_clashing() {
+        let conn = connection();
+        let goals = GoalRepository::new(&conn);
+        let a = goals.insert(&goal_input("2026-05-01", 1, "A")).unwrap();
+        let b = goals.insert(&goal_input("2026-05-01", 2, "B")).unwrap();
+        let c = goals.insert(&goal_input("2026-05-01", 3, "C")).unwrap();
+
+        goals
+            .set_orders("2026-05-01", &[(&c, 1), (&a, 2), (&b, 3)])
+            .unwrap();
+
+        let texts: Vec<String> = goals
+            .for_date("2026-05-01")
+            .unwrap()
+            .into_iter()
+            .map(|g| g.goal_text)
+            .collect();
+        assert_eq!(texts, ["C", "A", "B"]);
+    }
+
+    #[test]
+    fn carried_goals_point_at_the_original() {
+        let conn = connection();
+        let goals = GoalRepository::new(&conn);
+        let first = goals.insert(&goal_input("2026-05-01", 1, "Taxes")).unwrap();
+        let first = goals.get(&first).unwrap();
+
+        let second = goals.insert_carried(&first, "2026-05-02", 2, None).unwrap();
+        let second = goals.get(&second).unwrap();
+        assert_eq!(second.original_goal_id.as_deref(), Some(first.id.as_str()));
+        assert_eq!(second.carried_since.as_deref(), Some("2026-05-01"));
+
+        // Carrying the copy again keeps pointing at the first one
+        let third = goals
+            .insert_carried(&second, "2026-05-03", 1, None)
+            .unwrap();
+        let third = goals.get(&third).unwrap();
+        assert_eq!(third.original_goal_id, second.original_goal_id);
+        assert_eq!(third.carried_since.as_deref(), Some("2026-05-01"));
+
+        assert!(goals.has_lineage_on(&first, "2026-05-03").unwrap());
+        assert!(goals.has_lineage_on(&third, "2026-05-01").unwrap());
+        assert!(!goals.has_lineage_on(&first, "2026-05-04").unwrap());
+    }
+
+    #[test]
+    fn streaks_break_on_missed_and_empty_days() {
+        let conn = connection();
+        let goals = GoalRepository::new(&conn);
+        record(
+            &goals,
+            &[
+                ("2026-05-01", true),
+                ("2026-05-02", true),
+                ("2026-05-03", true),
+                ("2026-05-04", false),
+                ("2026-05-05", true),
+                ("2026-05-06", true),
+                // Nothing planned on the 7th
+                ("2026-05-08", true),
+                ("2026-05-09", true),
+                ("2026-05-10", false),
+            ],
+        );
+        let second = goals.insert(&goal_input("2026-05-09", 2, "Other")).unwrap();
+
+        let longest = goals
+            .longest_streak("2026-05-01", "2026-05-31")
+            .unwrap()
+            .unwrap();
+        assert_eq!(longest.days, 3);
+        assert_eq!(longest.start_date.as_deref(), Some("2026-05-01"));
+
+        // The 9th has an open goal, so only the 8th is left of that run
+        let current = goals.streak_through("2026-05-09").unwrap().unwrap();
+        assert_eq!(
+            (current.days, current.end_date.as_deref()),
+            (1, Some("2026-05-08"))
+        );
+
+        goals.toggle_completion(&second).unwrap();
+        // Still running on the 10th, which isn't done yet
+        assert_eq!(goals.streak_through("2026-05-10").unwrap().unwrap().days, 2);
+        assert!(goals.streak_through("2026-05-11").unwrap().is_none());
+
+        // A range starting mid-run only counts the days inside it
+        let clipped = goals
+            .longest_streak("2026-05-02", "2026-05-31")
+            .unwrap()
+            .unwrap();
+        assert_eq!(clipped.days, 2);
+    }
+
+    #[test]
+    fn totals_group_by_week_month_and_weekday() {
+        let conn = connection();
+        let goals = GoalRepository::new(&conn);
+        // Thursday 30 April to Monday 4 May
+        record(
+            &goals,
+            &[
+                ("2026-04-30", true),
+                ("2026-05-01", false),
+                ("2026-05-03", true),
+                ("2026-05-04", true),
+            ],
+        );
+
+        let totals = goals.totals("2026-04-01", "2026-05-31").unwrap();
+        assert_eq!((totals.total_goals, totals.completed_goals), (4, 3));
+        assert_eq!(totals.completion_rate, 0.75);
+        assert_eq!(totals.perfect_days, 3);
+
+        let days = goals.daily_totals("2026-04-01", "2026-05-31").unwrap();
+        assert_eq!(days.len(), 4);
+        assert_eq!(days[1].0, "2026-05-01");
+        assert_eq!(days[1].1.completion_rate, 0.0);
+
+        let weeks = goals.weekly_totals("2026-04-01", "2026-05-31").unwrap();
+        let week_keys: Vec<&str> = weeks.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(week_keys, ["2026-04-27", "2026-05-04"]);
+        assert_eq!(weeks[0].1.days_with_goals, 3);
+
+        let months = goals.monthly_totals("2026-04-01", "2026-05-31").unwrap();
+        assert_eq!(months[1].0, "2026-05-01");
+        assert_eq!(months[1].1.completed_goals, 2);
+
+        let weekdays = goals.weekday_totals("2026-04-01", "2026-05-31").unwrap();
+        assert_eq!(weekdays[0].0, "0");
+        assert_eq!(weekdays.len(), 4);
+
+        assert_eq!(
+            goals
+                .totals("2026-06-01", "2026-06-30")
+                .unwrap()
+                .total_goals,
+            0
+        );
+    }
+}
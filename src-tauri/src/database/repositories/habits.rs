@@ -0,0 +1,477 @@
+use crate::database::{Habit, HabitCheckIn, HabitInput, HabitSchedule, HabitStreak};
+use chrono::{Datelike, Duration, NaiveDate};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::collections::BTreeSet;
+use uuid::Uuid;
+
+const HABIT_COLUMNS: &str = "id, name, description, schedule_type, schedule_days,
+     times_per_week, target_value, unit, reminder_time, color, is_archived, created_at";
+
+fn habit_from_row(row: &Row) -> rusqlite::Result<Habit> {
+    let schedule = match row.get::<_, String>(3)?.as_str() {
+        "weekdays" => HabitSchedule::Weekdays {
+            days: row
+                .get::<_, Option<String>>(4)?
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|day| day.parse().ok())
+                .collect(),
+        },
+        "per_week" => HabitSchedule::PerWeek {
+            times: row.get::<_, Option<i32>>(5)?.unwrap_or(1),
+        },
+        _ => HabitSchedule::Daily,
+    };
+
+    Ok(Habit {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        schedule,
+        target_value: row.get(6)?,
+        unit: row.get(7)?,
+        reminder_time: row.get(8)?,
+        color: row.get(9)?,
+        is_archived: row.get::<_, Option<i32>>(10)?.unwrap_or(0) != 0,
+        created_at: row.get(11)?,
+    })
+}
+
+/// Columns of a check-in `c` joined to its habit `h`; whether it is complete
+/// follows the habit's current target.
+const CHECK_IN_COLUMNS: &str = "c.habit_id, c.checkin_date, c.value,
+     h.target_value IS NULL OR COALESCE(c.value, 0) >= h.target_value, c.created_at";
+
+fn check_in_from_row(row: &Row) -> rusqlite::Result<HabitCheckIn> {
+    Ok(HabitCheckIn {
+        habit_id: row.get(0)?,
+        checkin_date: row.get(1)?,
+        value: row.get(2)?,
+        is_complete: row.get::<_, i32>(3)? != 0,
+        created_at: row.get(4)?,
+    })
+}
+
+/// The schedule's columns: type, weekdays and times per week.
+fn schedule_columns(schedule: &HabitSchedule) -> (&'static str, Option<String>, Option<i32>) {
+    match schedule {
+        HabitSchedule::Daily => ("daily", None, None),
+        HabitSchedule::Weekdays { days } => (
+            "weekdays",
+            Some(
+                days.iter()
+                    .map(|day| day.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            None,
+        ),
+        HabitSchedule::PerWeek { times } => ("per_week", None, Some(*times)),
+    }
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// The stretches a streak is counted in, with whether each was met: every
+/// due day, or every week for a per-week habit.
+fn streak_units(
+    schedule: &HabitSchedule,
+    done: &BTreeSet<NaiveDate>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Vec<(NaiveDate, NaiveDate, bool)> {
+    match schedule {
+        HabitSchedule::PerWeek { times } => {
+            let monday = |date: NaiveDate| {
+                date - Duration::days(date.weekday().num_days_from_monday() as i64)
+            };
+            let mut units = Vec::new();
+            let mut week = monday(from);
+            while week <= to {
+                let end = week + Duration::days(6);
+                let count = done.range(week..=end).count() as i32;
+                units.push((week, end, count >= *times));
+                week += Duration::days(7);
+            }
+            units
+        }
+        _ => from
+            .iter_days()
+            .take_while(|day| *day <= to)
+            .filter(|day| schedule.is_due(*day))
+            .map(|day| (day, day, done.contains(&day)))
+            .collect(),
+    }
+}
+
+fn streak(run: &[(NaiveDate, NaiveDate, bool)]) -> HabitStreak {
+    HabitStreak {
+        length: run.len() as i32,
+        start_date: run.first().map(|unit| format_date(unit.0)),
+        end_date: run.last().map(|unit| format_date(unit.1)),
+    }
+}
+
+pub struct HabitRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> HabitRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        HabitRepository { conn }
+    }
+
+    pub fn get(&self, id: &str) -> rusqlite::Result<Habit> {
+        self.conn.query_row(
+            &format!("SELECT {} FROM habits WHERE id = ?", HABIT_COLUMNS),
+            params![id],
+            habit_from_row,
+        )
+    }
+
+    pub fn all(&self, include_archived: bool) -> rusqlite::Result<Vec<Habit>> {
+        self.conn
+            .prepare(&format!(
+                "SELECT {} FROM habits
+                 WHERE ?1 OR COALESCE(is_archived, 0) = 0
+                 ORDER BY created_at, name",
+                HABIT_COLUMNS
+            ))?
+            .query_map(params![include_archived], habit_from_row)?
+            .collect()
+    }
+
+    pub fn insert(&self, input: &HabitInput) -> rusqlite::Result<String> {
+        let id = Uuid::new_v4().to_string();
+        let (schedule_type, schedule_days, times_per_week) = schedule_columns(&input.schedule);
+        self.conn.execute(
+            "INSERT INTO habits (id, name, description, schedule_type, schedule_days,
+                                 times_per_week, target_value, unit, reminder_time, color)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                id,
+                input.name,
+                input.description,
+                schedule_type,
+                schedule_days,
+                times_per_week,
+                input.target_value,
+                input.unit,
+                input.reminder_time,
+                input.color
+            ],
+        )?;
+        Ok(id)
+    }
+
+    pub fn update(&self, id: &str, input: &HabitInput) -> rusqlite::Result<()> {
+        let (schedule_type, schedule_days, times_per_week) = schedule_columns(&input.schedule);
+        self.conn.execute(
+            "UPDATE habits
+             SET name = ?, description = ?, schedule_type = ?, schedule_days = ?,
+                 times_per_week = ?, target_value = ?, unit = ?, reminder_time = ?, color = ?
+             WHERE id = ?",
+            params![
+                input.name,
+                input.description,
+                schedule_type,
+                schedule_days,
+                times_per_week,
+                input.target_value,
+                input.unit,
+                input.reminder_time,
+                input.color,
+                id
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_archived(&self, id: &str, archived: bool) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE habits SET is_archived = ? WHERE id = ?",
+            params![archived, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete(&self, id: &str) -> rusqlite::Result<()> {
+        self.conn
+            .execute("DELETE FROM habit_checkins WHERE habit_id = ?", params![id])?;
+        self.conn
+            .execute("DELETE FROM habits WHERE id = ?", params![id])?;
+        Ok(())
+    }
+
+    /// Records the day's check-in, replacing any value already there.
+    pub fn check_in(&self, id: &str, date: &str, value: Option<f64>) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO habit_checkins (habit_id, checkin_date, value) VALUES (?1, ?2, ?3)
+             ON CONFLICT(habit_id, checkin_date) DO UPDATE SET value = ?3",
+            params![id, date, value],
+        )?;
+        Ok(())
+    }
+
+    pub fn remove_check_in(&self, id: &str, date: &str) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "DELETE FROM habit_checkins WHERE habit_id = ? AND checkin_date = ?",
+            params![id, date],
+        )?;
+        Ok(())
+    }
+
+    pub fn check_in_on(&self, id: &str, date: &str) -> rusqlite::Result<Option<HabitCheckIn>> {
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM habit_checkins c JOIN habits h ON h.id = c.habit_id
+                     WHERE c.habit_id = ? AND c.checkin_date = ?",
+                    CHECK_IN_COLUMNS
+                ),
+                params![id, date],
+                check_in_from_row,
+            )
+            .optional()
+    }
+
+    pub fn check_ins(
+        &self,
+        id: &str,
+        start_date: &str,
+        end_date: &str,
+    ) -> rusqlite::Result<Vec<HabitCheckIn>> {
+        self.conn
+            .prepare(&format!(
+                "SELECT {} FROM habit_checkins c JOIN habits h ON h.id = c.habit_id
+                 WHERE c.habit_id = ? AND c.checkin_date BETWEEN ? AND ?
+                 ORDER BY c.checkin_date",
+                CHECK_IN_COLUMNS
+            ))?
+            .query_map(params![id, start_date, end_date], check_in_from_row)?
+            .collect()
+    }
+
+    /// Days between the two dates with a complete check-in.
+    fn completed_days(
+        &self,
+        id: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> rusqlite::Result<BTreeSet<NaiveDate>> {
+        Ok(self
+            .check_ins(id, &format_date(start), &format_date(end))?
+            .into_iter()
+            .filter(|check_in| check_in.is_complete)
+            .filter_map(|check_in| check_in.checkin_date.parse().ok())
+            .collect())
+    }
+
+    /// The longest streak between the two dates; the earliest one on a tie.
+    /// Weeks of a per-week habit count if they overlap the range.
+    pub fn longest_streak(
+        &self,
+        habit: &Habit,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> rusqlite::Result<HabitStreak> {
+        let done = self.completed_days(
+            &habit.id,
+            start - Duration::days(6),
+            end + Duration::days(6),
+        )?;
+        let units = streak_units(&habit.schedule, &done, start, end);
+
+        let mut longest: &[_] = &[];
+        for run in units.split(|unit| !unit.2) {
+            if run.len() > longest.len() {
+                longest = run;
+            }
+        }
+        Ok(streak(longest))
+    }
+
+    /// The streak still going on `date`. The day, or the week of a per-week
+    /// habit, isn't over yet, so not having met it doesn't break the streak.
+    pub fn streak_through(&self, habit: &Habit, date: NaiveDate) -> rusqlite::Result<HabitStreak> {
+        let first: Option<String> = self.conn.query_row(
+            "SELECT MIN(checkin_date) FROM habit_checkins WHERE habit_id = ?",
+            params![habit.id],
+            |row| row.get(0),
+        )?;
+        let Some(first) = first
+            .and_then(|first| first.parse::<NaiveDate>().ok())
+            .filter(|first| *first <= date)
+        else {
+            return Ok(HabitStreak::default());
+        };
+
+        let done = self.completed_days(&habit.id, first, date)?;
+        let mut units = streak_units(&habit.schedule, &done, first, date);
+        if units.last().is_some_and(|unit| unit.1 >= date && !unit.2) {
+            units.pop();
+        }
+
+        let run = units.iter().rev().take_while(|unit| unit.2).count();
+        Ok(streak(&units[units.len() - run..]))
+    }
+
+    /// Habits with a reminder that hasn't gone out on `date` yet.
+    pub fn unreminded(&self, date: &str) -> rusqlite::Result<Vec<Habit>> {
+        self.conn
+            .prepare(&format!(
+                "SELECT {} FROM habits
+                 WHERE reminder_time IS NOT NULL
+                   AND COALESCE(is_archived, 0) = 0
+                   AND last_reminded_on IS NOT ?",
+                HABIT_COLUMNS
+            ))?
+            .query_map(params![date], habit_from_row)?
+            .collect()
+    }
+
+    pub fn set_reminded(&self, id: &str, date: &str) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE habits SET last_reminded_on = ? WHERE id = ?",
+            params![date, id],
+        )?;
+        Ok(())
+    }
+
+    /// Complete check-ins in the Monday-to-Sunday week holding `date`.
+    pub fn completed_in_week(&self, id: &str, date: NaiveDate) -> rusqlite::Result<i32> {
+        let monday = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+        Ok(self
+            .completed_days(id, monday, monday + Duration::days(6))?
+            .len() as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::fixtures::{connection, habit_input};
+    use chrono::Weekday;
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn schedules_round_trip() {
+        let conn = connection();
+        let habits = HabitRepository::new(&conn);
+
+        for schedule in [
+            HabitSchedule::Daily,
+            HabitSchedule::Weekdays {
+                days: vec![Weekday::Mon, Weekday::Thu],
+            },
+            HabitSchedule::PerWeek { times: 3 },
+        ] {
+            let id = habits.insert(&habit_input(schedule.clone(), None)).unwrap();
+            assert_eq!(habits.get(&id).unwrap().schedule, schedule);
+        }
+
+        let id = habits.all(false).unwrap()[0].id.clone();
+        habits.set_archived(&id, true).unwrap();
+        assert_eq!(habits.all(false).unwrap().len(), 2);
+        assert_eq!(habits.all(true).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn check_ins_complete_against_the_target() {
+        let conn = connection();
+        let habits = HabitRepository::new(&conn);
+        let id = habits
+            .insert(&habit_input(HabitSchedule::Daily, Some(20.0)))
+            .unwrap();
+
+        habits.check_in(&id, "2026-05-01", Some(12.0)).unwrap();
+        assert!(
+            !habits
+                .check_in_on(&id, "2026-05-01")
+                .unwrap()
+                .unwrap()
+                .is_complete
+        );
+
+        habits.check_in(&id, "2026-05-01", Some(25.0)).unwrap();
+        let check_in = habits.check_in_on(&id, "2026-05-01").unwrap().unwrap();
+        assert_eq!((check_in.value, check_in.is_complete), (Some(25.0), true));
+
+        habits.remove_check_in(&id, "2026-05-01").unwrap();
+        assert!(habits.check_in_on(&id, "2026-05-01").unwrap().is_none());
+    }
+
+    #[test]
+    fn weekday_streaks_skip_days_off() {
+        let conn = connection();
+        let habits = HabitRepository::new(&conn);
+        let id = habits
+            .insert(&habit_input(
+                HabitSchedule::Weekdays {
+                    days: vec![Weekday::Mon, Weekday::Wed, Weekday::Fri],
+                },
+                None,
+            ))
+            .unwrap();
+        let habit = habits.get(&id).unwrap();
+
+        // Mon 4th is missed, then Wed 6th through Mon 11th are kept
+        for day in ["2026-05-01", "2026-05-06", "2026-05-08", "2026-05-11"] {
+            habits.check_in(&id, day, None).unwrap();
+        }
+
+        let current = habits.streak_through(&habit, date("2026-05-12")).unwrap();
+        assert_eq!(current.length, 3);
+        assert_eq!(current.start_date.as_deref(), Some("2026-05-06"));
+
+        // Wednesday the 13th isn't over, so it doesn't break the streak yet
+        let current = habits.streak_through(&habit, date("2026-05-13")).unwrap();
+        assert_eq!(current.length, 3);
+        let current = habits.streak_through(&habit, date("2026-05-14")).unwrap();
+        assert_eq!(current.length, 0);
+
+        let longest = habits
+            .longest_streak(&habit, date("2026-05-01"), date("2026-05-31"))
+            .unwrap();
+        assert_eq!(longest.length, 3);
+        assert_eq!(longest.end_date.as_deref(), Some("2026-05-11"));
+    }
+
+    #[test]
+    fn per_week_streaks_count_weeks() {
+        let conn = connection();
+        let habits = HabitRepository::new(&conn);
+        let id = habits
+            .insert(&habit_input(HabitSchedule::PerWeek { times: 2 }, None))
+            .unwrap();
+        let habit = habits.get(&id).unwrap();
+
+        // Two weeks met, then one check-in so far in the week of the 18th
+        for day in [
+            "2026-05-04",
+            "2026-05-09",
+            "2026-05-11",
+            "2026-05-12",
+            "2026-05-18",
+        ] {
+            habits.check_in(&id, day, None).unwrap();
+        }
+        assert_eq!(
+            habits.completed_in_week(&id, date("2026-05-20")).unwrap(),
+            1
+        );
+
+        let current = habits.streak_through(&habit, date("2026-05-20")).unwrap();
+        assert_eq!(current.length, 2);
+        assert_eq!(current.start_date.as_deref(), Some("2026-05-04"));
+        assert_eq!(current.end_date.as_deref(), Some("2026-05-17"));
+
+        let current = habits.streak_through(&habit, date("2026-05-25")).unwrap();
+        assert_eq!(current.length, 0);
+    }
+}
@@ -0,0 +1,268 @@
+use crate::database::JournalEntry;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use uuid::Uuid;
+
+pub(crate) const ENTRY_COLUMNS: &str =
+    "id, entry_date, title, content, mood, created_at, updated_at";
+
+pub(crate) fn entry_from_row(row: &Row) -> rusqlite::Result<JournalEntry> {
+    Ok(JournalEntry {
+        id: row.get(0)?,
+        entry_date: row.get(1)?,
+        title: row.get(2)?,
+        content: row.get(3)?,
+        mood: row.get(4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+pub struct JournalRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> JournalRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        JournalRepository { conn }
+    }
+
+    pub fn get(&self, id: &str) -> rusqlite::Result<JournalEntry> {
+        self.conn.query_row(
+            &format!("SELECT {} FROM journal_entries WHERE id = ?", ENTRY_COLUMNS),
+            params![id],
+            entry_from_row,
+        )
+    }
+
+    /// The day's first entry; one without a time counts as first.
+    pub fn for_date(&self, date: &str) -> rusqlite::Result<Option<JournalEntry>> {
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM journal_entries WHERE entry_date = ?
+                     ORDER BY created_at, rowid
+                     LIMIT 1",
+                    ENTRY_COLUMNS
+                ),
+                params![date],
+                entry_from_row,
+            )
+            .optional()
+    }
+
+    /// Entries between the two dates, with either end left open, in the
+    /// order they were written.
+    pub fn between(
+        &self,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+    ) -> rusqlite::Result<Vec<JournalEntry>> {
+        self.conn
+            .prepare(&format!(
+                "SELECT {} FROM journal_entries
+                 WHERE (?1 IS NULL OR entry_date >= ?1) AND (?2 IS NULL OR entry_date <= ?2)
+                 ORDER BY entry_date, created_at, rowid",
+                ENTRY_COLUMNS
+            ))?
+            .query_map(params![start_date, end_date], entry_from_row)?
+            .collect()
+    }
+
+    /// Whether an entry with this date, time and text is already stored.
+    pub fn contains(
+        &self,
+        entry_date: &str,
+        created_at: Option<&str>,
+        content: &str,
+    ) -> rusqlite::Result<bool> {
+        self.conn
+            .prepare(
+                "SELECT 1 FROM journal_entries
+                 WHERE entry_date = ? AND created_at IS ? AND content = ?",
+            )?
+            .exists(params![entry_date, created_at, content])
+    }
+
+    pub fn insert(
+        &self,
+        entry_date: &str,
+        title: Option<&str>,
+        content: &str,
+        mood: Option<&str>,
+        created_at: Option<&str>,
+    ) -> rusqlite::Result<String> {
+        let id = Uuid::new_v4().to_string();
+        self.conn.execute(
+            "INSERT INTO journal_entries (id, entry_date, title, content, mood, created_at,
+                                          updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)",
+            params![id, entry_date, title, content, mood, created_at],
+        )?;
+        Ok(id)
+    }
+
+    pub fn update(
+        &self,
+        id: &str,
+        title: Option<&str>,
+        content: &str,
+        mood: Option<&str>,
+    ) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE journal_entries
+             SET title = ?, content = ?, mood = ?, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE id = ?",
+            params![title, content, mood, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete(&self, id: &str) -> rusqlite::Result<()> {
+        self.conn
+            .execute("DELETE FROM journal_entries WHERE id = ?", params![id])?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::fixtures::connection;
+    use crate::database::Database;
+
+    #[test]
+    fn entry_round_trip() {
+        let conn = connection();
+        let journal = JournalRepository::new(&conn);
+        assert!(journal.for_date("2026-05-01").unwrap().is_none());
+
+        let id = journal
+            .insert("2026-05-01", None, "Hello", None, None)
+            .unwrap();
+        journal
+            .update(&id, Some("Morning"), "Hello again", Some("good"))
+            .unwrap();
+
+        let entry = journal.for_date("2026-05-01").unwrap().unwrap();
+        assert_eq!(entry.id, id);
+        assert_eq!(entry.content, "Hello again");
+        assert_eq!(entry.mood.as_deref(), Some("good"));
+        assert_eq!(entry.title.as_deref(), Some("Morning"));
+        assert!(entry.updated_at.is_some());
+
+        journal.delete(&id).unwrap();
+        assert!(journal.get(&id).is_err());
+    }
+
+    #[test]
+    fn between_is_ordered_and_open_ended() {
+        let conn = connection();
+        let journal = JournalRepository::new(&conn);
+        for date in ["2026-05-03", "2026-05-01", "2026-05-02"] {
+            journal.insert(date, None, date, None, None).unwrap();
+        }
+
+        let dates: Vec<String> = journal
+            .between(None, Some("2026-05-02"))
+            .unwrap()
+            .into_iter()
+            .map(|e| e.entry_date)
+            .collect();
+        assert_eq!(dates, ["2026-05-01", "2026-05-02"]);
+        assert_eq!(journal.between(None, None).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn a_day_holds_several_entries_in_time_order() {
+        let conn = connection();
+        let journal = JournalRepository::new(&conn);
+        let evening = journal
+            .insert(
+                "2026-05-01",
+                Some("Evening"),
+                "Wound down",
+                Some("okay"),
+                Some("2026-05-01T20:00:00Z"),
+            )
+            .unwrap();
+        let morning = journal
+            .insert(
+                "2026-05-01",
+                Some("Morning pages"),
+                "Slept well",
+                Some("good"),
+                Some("2026-05-01T07:00:00Z"),
+            )
+            .unwrap();
+
+        let day: Vec<String> = journal
+            .between(Some("2026-05-01"), Some("2026-05-01"))
+            .unwrap()
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(day, [morning.clone(), evening]);
+        assert_eq!(journal.for_date("2026-05-01").unwrap().unwrap().id, morning);
+    }
+
+    #[test]
+    fn one_entry_a_day_tables_are_rebuilt_with_their_entries() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE journal_entries (
+                 id TEXT PRIMARY KEY,
+                 entry_date TEXT NOT NULL UNIQUE,
+                 content TEXT NOT NULL,
+                 mood TEXT
+             );
+             INSERT INTO journal_entries VALUES ('old', '2026-04-30', 'Before', 'good');",
+        )
+        .unwrap();
+        Database::run_migrations(&conn).unwrap();
+        // Running again leaves the rebuilt table alone
+        Database::run_migrations(&conn).unwrap();
+
+        let journal = JournalRepository::new(&conn);
+        let old = journal.get("old").unwrap();
+        assert_eq!((old.content.as_str(), old.created_at), ("Before", None));
+
+        let later = journal
+            .insert(
+                "2026-04-30",
+                None,
+                "After",
+                None,
+                Some("2026-04-30T21:00:00Z"),
+            )
+            .unwrap();
+        assert_eq!(journal.for_date("2026-04-30").unwrap().unwrap().id, "old");
+        assert_eq!(journal.between(None, None).unwrap()[1].id, later);
+    }
+
+    #[test]
+    fn failed_rebuild_is_rolled_back() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE journal_entries (
+                 id TEXT PRIMARY KEY,
+                 entry_date TEXT NOT NULL UNIQUE,
+                 content TEXT NOT NULL,
+                 mood TEXT
+             );
+             INSERT INTO journal_entries VALUES ('old', '2026-04-30', 'Before', 'good');
+             CREATE TABLE journal_entries_new (id TEXT);",
+        )
+        .unwrap();
+        assert!(Database::run_migrations(&conn).is_err());
+
+        assert!(conn.is_autocommit());
+        let content: String = conn
+            .query_row(
+                "SELECT content FROM journal_entries WHERE id = 'old'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(content, "Before");
+    }
+}
@@ -0,0 +1,15 @@
+pub mod goals;
+pub mod habits;
+pub mod journal;
+pub mod period_goals;
+pub mod settings;
+pub mod tasks;
+pub mod wellbeing;
+
+pub use goals::*;
+pub use habits::*;
+pub use journal::*;
+pub use period_goals::*;
+pub use settings::*;
+pub use tasks::*;
+pub use wellbeing::*;
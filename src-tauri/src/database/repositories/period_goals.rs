@@ -0,0 +1,186 @@
+use crate::database::{PeriodGoal, PeriodKey, UpsertPeriodGoalInput};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use uuid::Uuid;
+
+/// Columns of a period goal `p` with its linked daily goals counted.
+pub(crate) const PERIOD_GOAL_COLUMNS: &str =
+    "p.id, p.period_type, p.period_key, p.start_date, p.end_date, p.goal_text, p.goal_order,
+     p.is_completed,
+     (SELECT COUNT(*) FROM daily_goals d WHERE d.parent_goal_id = p.id),
+     (SELECT COALESCE(SUM(d.is_completed), 0) FROM daily_goals d WHERE d.parent_goal_id = p.id)";
+
+pub(crate) fn period_goal_from_row(row: &Row) -> rusqlite::Result<PeriodGoal> {
+    let is_completed = row.get::<_, i32>(7)? != 0;
+    let child_goals: i32 = row.get(8)?;
+    let completed_child_goals: i32 = row.get(9)?;
+
+    let progress = if child_goals > 0 {
+        completed_child_goals as f64 / child_goals as f64
+    } else if is_completed {
+        1.0
+    } else {
+        0.0
+    };
+
+    Ok(PeriodGoal {
+        id: row.get(0)?,
+        period: row.get(1)?,
+        period_key: row.get(2)?,
+        start_date: row.get(3)?,
+        end_date: row.get(4)?,
+        goal_text: row.get(5)?,
+        goal_order: row.get(6)?,
+        is_completed,
+        child_goals,
+        completed_child_goals,
+        progress,
+    })
+}
+
+pub struct PeriodGoalRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> PeriodGoalRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        PeriodGoalRepository { conn }
+    }
+
+    pub fn get(&self, id: &str) -> rusqlite::Result<PeriodGoal> {
+        self.conn.query_row(
+            &format!(
+                "SELECT {} FROM period_goals p WHERE p.id = ?",
+                PERIOD_GOAL_COLUMNS
+            ),
+            params![id],
+            period_goal_from_row,
+        )
+    }
+
+    pub fn for_period(&self, key: &PeriodKey) -> rusqlite::Result<Vec<PeriodGoal>> {
+        self.conn
+            .prepare(&format!(
+                "SELECT {} FROM period_goals p WHERE p.period_key = ? ORDER BY p.goal_order",
+                PERIOD_GOAL_COLUMNS
+            ))?
+            .query_map(params![key], period_goal_from_row)?
+            .collect()
+    }
+
+    /// The goal in a given slot of the period, if there is one.
+    pub fn id_at(&self, key: &PeriodKey, order: i32) -> rusqlite::Result<Option<String>> {
+        self.conn
+            .query_row(
+                "SELECT id FROM period_goals WHERE period_key = ? AND goal_order = ?",
+                params![key, order],
+                |row| row.get(0),
+            )
+            .optional()
+    }
+
+    pub fn insert(&self, input: &UpsertPeriodGoalInput) -> rusqlite::Result<String> {
+        let id = Uuid::new_v4().to_string();
+        let key = &input.period_key;
+        self.conn.execute(
+            "INSERT INTO period_goals (id, period_type, period_key, start_date, end_date,
+                                       goal_text, goal_order)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+            params![
+                id,
+                key.period,
+                key,
+                key.start.format("%Y-%m-%d").to_string(),
+                key.end().format("%Y-%m-%d").to_string(),
+                input.goal_text,
+                input.goal_order
+            ],
+        )?;
+        Ok(id)
+    }
+
+    pub fn set_text(&self, id: &str, text: &str) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE period_goals SET goal_text = ? WHERE id = ?",
+            params![text, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn toggle_completion(&self, id: &str) -> rusqlite::Result<PeriodGoal> {
+        self.conn.execute(
+            "UPDATE period_goals SET is_completed = NOT is_completed WHERE id = ?",
+            params![id],
+        )?;
+        self.get(id)
+    }
+
+    /// Deletes the goal; daily goals linked to it stay, unlinked.
+    pub fn delete(&self, id: &str) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE daily_goals SET parent_goal_id = NULL WHERE parent_goal_id = ?",
+            params![id],
+        )?;
+        self.conn
+            .execute("DELETE FROM period_goals WHERE id = ?", params![id])?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::fixtures::{connection, goal_input, period_goal_input};
+    use crate::database::{GoalRepository, UpsertGoalInput};
+
+    #[test]
+    fn period_keys_round_trip_with_their_bounds() {
+        for (key, start, end) in [
+            ("2026-W01", "2025-12-29", "2026-01-04"),
+            ("2026-W53", "2026-12-28", "2027-01-03"),
+            ("2026-02", "2026-02-01", "2026-02-28"),
+            ("2026-Q4", "2026-10-01", "2026-12-31"),
+            ("2026", "2026-01-01", "2026-12-31"),
+        ] {
+            let parsed: PeriodKey = key.parse().unwrap();
+            assert_eq!(parsed.to_string(), key);
+            assert_eq!(parsed.start.to_string(), start);
+            assert_eq!(parsed.end().to_string(), end);
+        }
+
+        for bad in ["2026-W54", "2026-13", "2026-Q5", "26", "2026-1", "2026-W1x"] {
+            assert!(bad.parse::<PeriodKey>().is_err(), "{} parsed", bad);
+        }
+    }
+
+    #[test]
+    fn progress_rolls_up_from_linked_daily_goals() {
+        let conn = connection();
+        let periods = PeriodGoalRepository::new(&conn);
+        let goals = GoalRepository::new(&conn);
+
+        let id = periods
+            .insert(&period_goal_input("2026-05", "Ship the beta"))
+            .unwrap();
+        assert_eq!(periods.get(&id).unwrap().progress, 0.0);
+
+        for date in ["2026-05-04", "2026-05-05"] {
+            let child = goals
+                .insert(&UpsertGoalInput {
+                    parent_goal_id: Some(id.clone()),
+                    ..goal_input(date, 1, "Work on the beta")
+                })
+                .unwrap();
+            if date == "2026-05-04" {
+                goals.toggle_completion(&child).unwrap();
+            }
+        }
+
+        let goal = &periods.for_period(&"2026-05".parse().unwrap()).unwrap()[0];
+        assert_eq!((goal.child_goals, goal.completed_child_goals), (2, 1));
+        assert_eq!(goal.progress, 0.5);
+
+        periods.delete(&id).unwrap();
+        let orphans = goals.for_date("2026-05-04").unwrap();
+        assert_eq!(orphans[0].parent_goal_id, None);
+    }
+}
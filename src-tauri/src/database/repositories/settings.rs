@@ -0,0 +1,38 @@
+use rusqlite::{params, Connection, OptionalExtension};
+
+pub struct SettingsRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> SettingsRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        SettingsRepository { conn }
+    }
+
+    pub fn get(&self, key: &str) -> rusqlite::Result<Option<String>> {
+        self.conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = ?",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()
+    }
+
+    pub fn set(&self, key: &str, value: &str) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = ?2",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    pub fn flag(&self, key: &str) -> rusqlite::Result<bool> {
+        Ok(self.get(key)?.as_deref() == Some("true"))
+    }
+
+    pub fn set_flag(&self, key: &str, on: bool) -> rusqlite::Result<()> {
+        self.set(key, if on { "true" } else { "false" })
+    }
+}
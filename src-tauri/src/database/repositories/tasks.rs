@@ -0,0 +1,605 @@
+use crate::database::{CreateTaskInput, Date, Task, Time, UpdateTaskInput};
+use rusqlite::{params, Connection, Row, ToSql};
+use uuid::Uuid;
+
+/// Columns read by `task_from_row`, including the time tracked so far
+/// (a running timer counts up to now) and whether an open prerequisite
+/// blocks the task.
+pub(crate) const TASK_COLUMNS: &str =
+    "id, title, description, scheduled_date, scheduled_time,
+     duration_minutes, priority, is_completed, reminder_minutes, color, created_at,
+     (SELECT COALESCE(SUM(strftime('%s', COALESCE(ended_at, 'now')) - strftime('%s', started_at)), 0) / 60
+      FROM time_entries WHERE time_entries.task_id = tasks.id),
+     EXISTS(SELECT 1 FROM task_dependencies d JOIN tasks p ON p.id = d.depends_on_id
+            WHERE d.task_id = tasks.id AND p.is_completed = 0),
+     end_date, is_all_day, time_zone, completed_at";
+
+pub(crate) fn task_from_row(row: &Row) -> rusqlite::Result<Task> {
+    Ok(Task {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        description: row.get(2)?,
+        scheduled_date: row.get(3)?,
+        scheduled_time: row.get(4)?,
+        duration_minutes: row.get(5)?,
+        priority: row.get(6)?,
+        is_completed: row.get::<_, i32>(7)? != 0,
+        reminder_minutes: row.get(8)?,
+        color: row.get(9)?,
+        created_at: row.get(10)?,
+        actual_minutes: row.get(11)?,
+        is_blocked: row.get(12)?,
+        end_date: row.get(13)?,
+        is_all_day: row.get::<_, Option<i32>>(14)?.unwrap_or(0) != 0,
+        occurrence_date: row.get(3)?,
+        time_zone: row.get(15)?,
+        completed_at: row.get(16)?,
+    })
+}
+
+/// Reads and writes `tasks` on whatever connection or transaction it is
+/// given. Validation beyond what SQLite enforces is left to the caller.
+pub struct TaskRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> TaskRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        TaskRepository { conn }
+    }
+
+    fn query(&self, filter: &str, params: &[&dyn ToSql]) -> rusqlite::Result<Vec<Task>> {
+        self.conn
+            .prepare(&format!("SELECT {} FROM tasks {}", TASK_COLUMNS, filter))?
+            .query_map(params, task_from_row)?
+            .collect()
+    }
+
+    pub fn get(&self, id: &str) -> rusqlite::Result<Task> {
+        self.conn.query_row(
+            &format!("SELECT {} FROM tasks WHERE id = ?", TASK_COLUMNS),
+            params![id],
+            task_from_row,
+        )
+    }
+
+    /// Tasks that may occupy a day between the two dates, once each. Timed
+    /// tasks are matched two days either side, since the zone they were
+    /// planned in can put them on a neighbouring day; callers narrow them
+    /// down with `task_days` or `TimedSlot`.
+    pub fn overlapping(&self, start_date: &str, end_date: &str) -> rusqlite::Result<Vec<Task>> {
+        self.query(
+            "WHERE scheduled_date <= date(?2, '+2 days')
+               AND (COALESCE(end_date, scheduled_date) >= ?1
+                    OR (scheduled_time IS NOT NULL
+                        AND date(scheduled_date || ' ' || scheduled_time,
+                                 '+' || MAX(COALESCE(duration_minutes, 0) - 1, 0) || ' minutes') >= date(?1, '-2 days')))
+             ORDER BY scheduled_date, scheduled_time",
+            params![start_date, end_date],
+        )
+    }
+
+    /// Tasks scheduled between the two dates, with either end left open.
+    pub fn scheduled_between(
+        &self,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+    ) -> rusqlite::Result<Vec<Task>> {
+        self.query(
+            "WHERE (?1 IS NULL OR scheduled_date >= ?1) AND (?2 IS NULL OR scheduled_date <= ?2)
+             ORDER BY scheduled_date, scheduled_time",
+            params![start_date, end_date],
+        )
+    }
+
+    /// Tasks `task_id` waits on.
+    pub fn prerequisites(&self, task_id: &str) -> rusqlite::Result<Vec<Task>> {
+        self.query(
+            "WHERE id IN (SELECT depends_on_id FROM task_dependencies WHERE task_id = ?)
+             ORDER BY scheduled_date, scheduled_time",
+            params![task_id],
+        )
+    }
+
+    /// Tasks linked to the daily goal.
+    pub fn linked_to_goal(&self, goal_id: &str) -> rusqlite::Result<Vec<Task>> {
+        self.query(
+            "WHERE id IN (SELECT task_id FROM goal_tasks WHERE goal_id = ?)
+             ORDER BY scheduled_date, scheduled_time",
+            params![goal_id],
+        )
+    }
+
+    /// Tasks waiting on `task_id`.
+    pub fn dependents(&self, task_id: &str) -> rusqlite::Result<Vec<Task>> {
+        self.query(
+            "WHERE id IN (SELECT task_id FROM task_dependencies WHERE depends_on_id = ?)
+             ORDER BY scheduled_date, scheduled_time",
+            params![task_id],
+        )
+    }
+
+    /// Open timed tasks with a reminder still to send. Tasks planned in
+    /// another zone can start a day either side of ours, so this reaches
+    /// from yesterday to the day after tomorrow.
+    pub fn with_pending_reminders(&self) -> rusqlite::Result<Vec<Task>> {
+        self.query(
+            "WHERE scheduled_date BETWEEN date('now', '-1 day') AND date('now', '+2 days')
+               AND scheduled_time IS NOT NULL
+               AND reminder_minutes IS NOT NULL
+               AND is_completed = 0",
+            &[],
+        )
+    }
+
+    /// Share of each day's tasks that are done, for the days between the
+    /// two dates that have any.
+    pub fn completion_rates(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> rusqlite::Result<Vec<(String, f64)>> {
+        self.conn
+            .prepare(
+                "SELECT scheduled_date, AVG(is_completed)
+                 FROM tasks
+                 WHERE scheduled_date BETWEEN ? AND ?
+                 GROUP BY scheduled_date",
+            )?
+            .query_map(params![start_date, end_date], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .collect()
+    }
+
+    /// Minutes tracked on each local day between the two dates, counting
+    /// time towards the day it was tracked on rather than the task's day.
+    pub fn tracked_minutes(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> rusqlite::Result<Vec<(String, i32)>> {
+        self.conn
+            .prepare(
+                "SELECT date(started_at, 'localtime') AS day,
+                        SUM(strftime('%s', COALESCE(ended_at, 'now')) - strftime('%s', started_at)) / 60
+                 FROM time_entries
+                 WHERE date(started_at, 'localtime') BETWEEN ? AND ?
+                 GROUP BY day",
+            )?
+            .query_map(params![start_date, end_date], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .collect()
+    }
+
+    /// Full ids starting with `prefix`, at most two so callers can tell a
+    /// unique match from an ambiguous one.
+    pub fn ids_with_prefix(&self, prefix: &str) -> rusqlite::Result<Vec<String>> {
+        self.conn
+            .prepare("SELECT id FROM tasks WHERE id LIKE ? || '%' LIMIT 2")?
+            .query_map(params![prefix], |row| row.get(0))?
+            .collect()
+    }
+
+    /// Inserts the task and returns its id. `time_zone` is stored as given.
+    pub fn insert(
+        &self,
+        input: &CreateTaskInput,
+        time_zone: Option<&str>,
+    ) -> rusqlite::Result<String> {
+        let id = Uuid::new_v4().to_string();
+
+        self.conn.execute(
+            "INSERT INTO tasks (id, title, description, scheduled_date, scheduled_time,
+                               duration_minutes, priority, reminder_minutes, color,
+                               end_date, is_all_day, time_zone)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                id,
+                input.title,
+                input.description,
+                input.scheduled_date,
+                input.scheduled_time,
+                input.duration_minutes.unwrap_or(30),
+                input.priority.unwrap_or_default(),
+                input.reminder_minutes,
+                input.color,
+                input.end_date,
+                input.is_all_day.unwrap_or(false),
+                time_zone,
+            ],
+        )?;
+
+        Ok(id)
+    }
+
+    /// Writes the fields set in `input`. A task given a time without a
+    /// zone keeps its zone, or gets `default_zone` if it had none. Returns
+    /// `false` if there was nothing to write.
+    pub fn update(&self, input: &UpdateTaskInput, default_zone: &str) -> rusqlite::Result<bool> {
+        // Build update query dynamically based on provided fields
+        let mut updates = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+
+        if let Some(ref title) = input.title {
+            updates.push("title = ?");
+            values.push(title);
+        }
+        if let Some(ref description) = input.description {
+            updates.push("description = ?");
+            values.push(description);
+        }
+        if let Some(ref scheduled_date) = input.scheduled_date {
+            updates.push("scheduled_date = ?");
+            values.push(scheduled_date);
+        }
+        // Making a task all-day drops its time unless a new one is given
+        if input.clear_scheduled_time
+            || (input.is_all_day == Some(true) && input.scheduled_time.is_none())
+        {
+            updates.push("scheduled_time = NULL");
+        } else if let Some(ref scheduled_time) = input.scheduled_time {
+            updates.push("scheduled_time = ?");
+            values.push(scheduled_time);
+        }
+        if let Some(ref time_zone) = input.time_zone {
+            updates.push("time_zone = ?");
+            values.push(time_zone);
+        } else if input.scheduled_time.is_some() {
+            updates.push("time_zone = COALESCE(time_zone, ?)");
+            values.push(&default_zone);
+        }
+        if let Some(ref duration) = input.duration_minutes {
+            updates.push("duration_minutes = ?");
+            values.push(duration);
+        }
+        if let Some(ref priority) = input.priority {
+            updates.push("priority = ?");
+            values.push(priority);
+        }
+        if let Some(ref is_completed) = input.is_completed {
+            updates.push("is_completed = ?");
+            values.push(is_completed);
+            // Already done tasks keep the time they were first done
+            updates.push(
+                "completed_at = CASE WHEN ? THEN COALESCE(completed_at, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')) END",
+            );
+            values.push(is_completed);
+        }
+        if let Some(ref reminder) = input.reminder_minutes {
+            updates.push("reminder_minutes = ?");
+            values.push(reminder);
+        }
+        if let Some(ref color) = input.color {
+            updates.push("color = ?");
+            values.push(color);
+        }
+        if input.clear_end_date {
+            updates.push("end_date = NULL");
+        } else if let Some(ref end_date) = input.end_date {
+            updates.push("end_date = ?");
+            values.push(end_date);
+        }
+        if let Some(ref is_all_day) = input.is_all_day {
+            updates.push("is_all_day = ?");
+            values.push(is_all_day);
+        }
+
+        if updates.is_empty() {
+            return Ok(false);
+        }
+
+        values.push(&input.id);
+        self.conn.execute(
+            &format!("UPDATE tasks SET {} WHERE id = ?", updates.join(", ")),
+            values.as_slice(),
+        )?;
+
+        Ok(true)
+    }
+
+    pub fn toggle_completion(&self, id: &str) -> rusqlite::Result<Task> {
+        self.conn.execute(
+            "UPDATE tasks
+             SET is_completed = NOT is_completed,
+                 completed_at = CASE WHEN is_completed THEN NULL
+                                     ELSE strftime('%Y-%m-%dT%H:%M:%SZ', 'now') END
+             WHERE id = ?",
+            params![id],
+        )?;
+        self.get(id)
+    }
+
+    /// Sets completion as read from elsewhere, such as an import, with the
+    /// time it happened if known.
+    pub fn set_completed(
+        &self,
+        id: &str,
+        is_completed: bool,
+        completed_at: Option<&str>,
+    ) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE tasks SET is_completed = ?, completed_at = ? WHERE id = ?",
+            params![is_completed, completed_at.filter(|_| is_completed), id],
+        )?;
+        Ok(())
+    }
+
+    /// Backdates the task, for imports that say when it was created.
+    /// Gives an untimed task on `date` its planned start, keeping its zone
+    /// if it has one. Returns false if the task has moved or been given a
+    /// time since it was planned.
+    pub fn schedule_untimed(
+        &self,
+        id: &str,
+        date: Date,
+        time: Time,
+        zone: &str,
+    ) -> rusqlite::Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE tasks SET scheduled_time = ?, time_zone = COALESCE(time_zone, ?)
+             WHERE id = ? AND scheduled_date = ? AND scheduled_time IS NULL",
+            params![time, zone, id, date],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Marks the task's reminder as sent.
+    pub fn clear_reminder(&self, id: &str) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE tasks SET reminder_minutes = NULL WHERE id = ?",
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_created_at(&self, id: &str, created_at: &str) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE tasks SET created_at = ? WHERE id = ?",
+            params![created_at, id],
+        )?;
+        Ok(())
+    }
+
+    /// Deletes the task along with its tracked time, focus sessions and
+    /// dependency links.
+    /// Deletes the task and everything hanging off it. Returns false if
+    /// there was no such task.
+    pub fn delete(&self, id: &str) -> rusqlite::Result<bool> {
+        self.conn
+            .execute("DELETE FROM time_entries WHERE task_id = ?", params![id])?;
+        self.conn
+            .execute("DELETE FROM focus_cycles WHERE task_id = ?", params![id])?;
+        self.conn
+            .execute("DELETE FROM focus_sessions WHERE task_id = ?", params![id])?;
+        self.conn.execute(
+            "DELETE FROM task_dependencies WHERE task_id = ?1 OR depends_on_id = ?1",
+            params![id],
+        )?;
+        self.conn
+            .execute("DELETE FROM goal_tasks WHERE task_id = ?", params![id])?;
+        let deleted = self
+            .conn
+            .execute("DELETE FROM tasks WHERE id = ?", params![id])?;
+        Ok(deleted > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::fixtures::{connection, no_changes, task_input};
+    use crate::database::{Database, Priority, Time};
+
+    #[test]
+    fn insert_fills_in_defaults() {
+        let conn = connection();
+        let tasks = TaskRepository::new(&conn);
+
+        let id = tasks
+            .insert(&task_input("Write", "2026-05-01"), None)
+            .unwrap();
+        let task = tasks.get(&id).unwrap();
+
+        assert_eq!(task.title, "Write");
+        assert_eq!(task.duration_minutes, 30);
+        assert_eq!(task.priority, "medium");
+        assert_eq!(task.occurrence_date, "2026-05-01");
+        assert!(!task.is_completed && !task.is_blocked && !task.is_all_day);
+    }
+
+    #[test]
+    fn overlapping_finds_spanning_and_late_tasks() {
+        let conn = connection();
+        let tasks = TaskRepository::new(&conn);
+
+        let mut trip = task_input("Trip", "2026-05-01");
+        trip.end_date = Some("2026-05-03".parse().unwrap());
+        tasks.insert(&trip, None).unwrap();
+
+        let mut late = task_input("Late", "2026-04-25");
+        late.scheduled_time = Some(Time("23:00".parse().unwrap()));
+        late.duration_minutes = Some(120);
+        tasks.insert(&late, Some("UTC")).unwrap();
+
+        tasks
+            .insert(&task_input("Early", "2026-04-20"), None)
+            .unwrap();
+
+        let titles: Vec<String> = tasks
+            .overlapping("2026-05-02", "2026-05-02")
+            .unwrap()
+            .into_iter()
+            .map(|t| t.title)
+            .collect();
+        assert_eq!(titles, ["Trip"]);
+
+        let titles: Vec<String> = tasks
+            .overlapping("2026-04-26", "2026-04-26")
+            .unwrap()
+            .into_iter()
+            .map(|t| t.title)
+            .collect();
+        assert_eq!(titles, ["Late"]);
+    }
+
+    #[test]
+    fn later_migration_runs_leave_zoneless_tasks_alone() {
+        let conn = connection();
+        let tasks = TaskRepository::new(&conn);
+
+        let mut timed = task_input("Call", "2026-05-01");
+        timed.scheduled_time = Some(Time("09:00".parse().unwrap()));
+        let id = tasks.insert(&timed, None).unwrap();
+
+        Database::run_migrations(&conn).unwrap();
+        assert_eq!(tasks.get(&id).unwrap().time_zone, None);
+    }
+
+    #[test]
+    fn legacy_times_are_normalized_rather_than_dropped() {
+        let conn = connection();
+        for (id, time) in [("a", "9:00"), ("b", "9:30 PM"), ("c", "7am"), ("d", "soon")] {
+            conn.execute(
+                "INSERT INTO tasks (id, title, scheduled_date, scheduled_time, time_zone)
+                 VALUES (?, 'Task', '2026-05-01', ?, 'UTC')",
+                [id, time],
+            )
+            .unwrap();
+        }
+
+        Database::run_migrations(&conn).unwrap();
+        let tasks = TaskRepository::new(&conn);
+        let times: Vec<Option<String>> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|id| tasks.get(id).unwrap().scheduled_time)
+            .collect();
+        assert_eq!(
+            times,
+            [
+                Some("09:00".to_string()),
+                Some("21:30".to_string()),
+                Some("07:00".to_string()),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn completion_time_is_kept_while_done() {
+        let conn = connection();
+        let tasks = TaskRepository::new(&conn);
+        let id = tasks
+            .insert(&task_input("Ship", "2026-05-01"), None)
+            .unwrap();
+
+        let done = tasks.toggle_completion(&id).unwrap();
+        let completed_at = done.completed_at.clone().unwrap();
+        assert!(completed_at.ends_with('Z'));
+
+        // Saving it as done again keeps the first time
+        let mut again = no_changes(&id);
+        again.is_completed = Some(true);
+        tasks.update(&again, "UTC").unwrap();
+        assert_eq!(tasks.get(&id).unwrap().completed_at, Some(completed_at));
+
+        assert_eq!(tasks.toggle_completion(&id).unwrap().completed_at, None);
+        tasks
+            .set_completed(&id, true, Some("2026-05-02T00:00:00Z"))
+            .unwrap();
+        let task = tasks.get(&id).unwrap();
+        assert!(task.is_completed);
+        assert_eq!(task.completed_at.as_deref(), Some("2026-05-02T00:00:00Z"));
+    }
+
+    #[test]
+    fn planned_time_is_only_set_on_an_untimed_task_still_on_its_day() {
+        let conn = connection();
+        let tasks = TaskRepository::new(&conn);
+        let id = tasks
+            .insert(&task_input("Read", "2026-05-01"), None)
+            .unwrap();
+        let day: Date = "2026-05-01".parse().unwrap();
+        let nine: Time = "09:00".parse().unwrap();
+
+        assert!(!tasks
+            .schedule_untimed(&id, "2026-05-02".parse().unwrap(), nine, "UTC")
+            .unwrap());
+        assert!(tasks.schedule_untimed(&id, day, nine, "UTC").unwrap());
+        assert!(!tasks
+            .schedule_untimed(&id, day, "10:00".parse().unwrap(), "UTC")
+            .unwrap());
+
+        let task = tasks.get(&id).unwrap();
+        assert_eq!(task.scheduled_time.as_deref(), Some("09:00"));
+        assert_eq!(task.time_zone.as_deref(), Some("UTC"));
+    }
+
+    #[test]
+    fn update_writes_only_given_fields() {
+        let conn = connection();
+        let tasks = TaskRepository::new(&conn);
+        let id = tasks
+            .insert(&task_input("Draft", "2026-05-01"), None)
+            .unwrap();
+
+        assert!(!tasks.update(&no_changes(&id), "UTC").unwrap());
+
+        let mut change = no_changes(&id);
+        change.priority = Some(Priority::High);
+        change.scheduled_time = Some("09:30".parse().unwrap());
+        assert!(tasks.update(&change, "Europe/Paris").unwrap());
+
+        let task = tasks.get(&id).unwrap();
+        assert_eq!(task.title, "Draft");
+        assert_eq!(task.priority, "high");
+        assert_eq!(task.scheduled_time.as_deref(), Some("09:30"));
+        assert_eq!(task.time_zone.as_deref(), Some("Europe/Paris"));
+
+        let mut all_day = no_changes(&id);
+        all_day.is_all_day = Some(true);
+        tasks.update(&all_day, "UTC").unwrap();
+        assert_eq!(tasks.get(&id).unwrap().scheduled_time, None);
+    }
+
+    #[test]
+    fn blocked_until_prerequisite_is_done() {
+        let conn = connection();
+        let tasks = TaskRepository::new(&conn);
+        let first = tasks
+            .insert(&task_input("First", "2026-05-01"), None)
+            .unwrap();
+        let second = tasks
+            .insert(&task_input("Second", "2026-05-02"), None)
+            .unwrap();
+        conn.execute(
+            "INSERT INTO task_dependencies (task_id, depends_on_id) VALUES (?, ?)",
+            params![second, first],
+        )
+        .unwrap();
+
+        assert!(tasks.get(&second).unwrap().is_blocked);
+        assert_eq!(tasks.dependents(&first).unwrap()[0].id, second);
+
+        tasks.toggle_completion(&first).unwrap();
+        assert!(!tasks.get(&second).unwrap().is_blocked);
+
+        assert!(tasks.delete(&first).unwrap());
+        assert!(tasks.prerequisites(&second).unwrap().is_empty());
+        assert_eq!(tasks.scheduled_between(None, None).unwrap().len(), 1);
+        assert!(!tasks.delete(&first).unwrap());
+    }
+
+    #[test]
+    fn ids_with_prefix_stops_at_two() {
+        let conn = connection();
+        let tasks = TaskRepository::new(&conn);
+        for day in ["2026-05-01", "2026-05-02", "2026-05-03"] {
+            tasks.insert(&task_input("Task", day), None).unwrap();
+        }
+
+        assert_eq!(tasks.ids_with_prefix("").unwrap().len(), 2);
+        assert!(tasks.ids_with_prefix("not-an-id").unwrap().is_empty());
+    }
+}
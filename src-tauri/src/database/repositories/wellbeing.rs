@@ -0,0 +1,363 @@
+use crate::database::{
+    MetricKind, MetricValue, WellbeingCheckIn, WellbeingCheckInInput, WellbeingMetric,
+    WellbeingMetricInput,
+};
+use rusqlite::{params, Connection, Row};
+use uuid::Uuid;
+
+const CHECK_IN_COLUMNS: &str = "id, entry_date, recorded_at, mood, energy, stress, note";
+
+fn check_in_from_row(row: &Row) -> rusqlite::Result<WellbeingCheckIn> {
+    Ok(WellbeingCheckIn {
+        id: row.get(0)?,
+        entry_date: row.get(1)?,
+        recorded_at: row.get(2)?,
+        mood: row
+            .get::<_, Option<String>>(3)?
+            .and_then(|mood| mood.parse().ok()),
+        energy: row.get(4)?,
+        stress: row.get(5)?,
+        note: row.get(6)?,
+    })
+}
+
+const METRIC_COLUMNS: &str = "id, name, kind, unit, created_at";
+
+fn metric_from_row(row: &Row) -> rusqlite::Result<WellbeingMetric> {
+    Ok(WellbeingMetric {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        kind: match row.get::<_, String>(2)?.as_str() {
+            "boolean" => MetricKind::Boolean,
+            _ => MetricKind::Number,
+        },
+        unit: row.get(3)?,
+        created_at: row.get(4)?,
+    })
+}
+
+pub struct WellbeingRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> WellbeingRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        WellbeingRepository { conn }
+    }
+
+    pub fn check_in(&self, id: &str) -> rusqlite::Result<WellbeingCheckIn> {
+        self.conn.query_row(
+            &format!(
+                "SELECT {} FROM wellbeing_checkins WHERE id = ?",
+                CHECK_IN_COLUMNS
+            ),
+            params![id],
+            check_in_from_row,
+        )
+    }
+
+    /// Check-ins between the two dates, earliest first.
+    pub fn check_ins_between(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> rusqlite::Result<Vec<WellbeingCheckIn>> {
+        self.conn
+            .prepare(&format!(
+                "SELECT {} FROM wellbeing_checkins
+                 WHERE entry_date BETWEEN ? AND ?
+                 ORDER BY entry_date, recorded_at",
+                CHECK_IN_COLUMNS
+            ))?
+            .query_map(params![start_date, end_date], check_in_from_row)?
+            .collect()
+    }
+
+    pub fn insert_check_in(
+        &self,
+        input: &WellbeingCheckInInput,
+        recorded_at: &str,
+    ) -> rusqlite::Result<String> {
+        let id = Uuid::new_v4().to_string();
+        self.conn.execute(
+            "INSERT INTO wellbeing_checkins (id, entry_date, recorded_at, mood, energy, stress,
+                                             note)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+            params![
+                id,
+                input.entry_date,
+                recorded_at,
+                input.mood,
+                input.energy,
+                input.stress,
+                input.note
+            ],
+        )?;
+        Ok(id)
+    }
+
+    pub fn update_check_in(
+        &self,
+        id: &str,
+        input: &WellbeingCheckInInput,
+        recorded_at: &str,
+    ) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE wellbeing_checkins
+             SET entry_date = ?, recorded_at = ?, mood = ?, energy = ?, stress = ?, note = ?
+             WHERE id = ?",
+            params![
+                input.entry_date,
+                recorded_at,
+                input.mood,
+                input.energy,
+                input.stress,
+                input.note,
+                id
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_check_in(&self, id: &str) -> rusqlite::Result<()> {
+        self.conn
+            .execute("DELETE FROM wellbeing_checkins WHERE id = ?", params![id])?;
+        Ok(())
+    }
+
+    /// Sets the night's sleep for the day, or clears it with `None`.
+    pub fn set_sleep(&self, date: &str, hours: Option<f64>) -> rusqlite::Result<()> {
+        match hours {
+            Some(hours) => self.conn.execute(
+                "INSERT INTO wellbeing_days (entry_date, sleep_hours) VALUES (?1, ?2)
+                 ON CONFLICT(entry_date) DO UPDATE SET sleep_hours = ?2",
+                params![date, hours],
+            )?,
+            None => self.conn.execute(
+                "DELETE FROM wellbeing_days WHERE entry_date = ?",
+                params![date],
+            )?,
+        };
+        Ok(())
+    }
+
+    pub fn sleep_between(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> rusqlite::Result<Vec<(String, f64)>> {
+        self.conn
+            .prepare(
+                "SELECT entry_date, sleep_hours FROM wellbeing_days
+                 WHERE entry_date BETWEEN ? AND ? AND sleep_hours IS NOT NULL
+                 ORDER BY entry_date",
+            )?
+            .query_map(params![start_date, end_date], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .collect()
+    }
+
+    pub fn metric(&self, id: &str) -> rusqlite::Result<WellbeingMetric> {
+        self.conn.query_row(
+            &format!(
+                "SELECT {} FROM wellbeing_metrics WHERE id = ?",
+                METRIC_COLUMNS
+            ),
+            params![id],
+            metric_from_row,
+        )
+    }
+
+    pub fn metrics(&self) -> rusqlite::Result<Vec<WellbeingMetric>> {
+        self.conn
+            .prepare(&format!(
+                "SELECT {} FROM wellbeing_metrics ORDER BY created_at, name",
+                METRIC_COLUMNS
+            ))?
+            .query_map([], metric_from_row)?
+            .collect()
+    }
+
+    pub fn insert_metric(&self, input: &WellbeingMetricInput) -> rusqlite::Result<String> {
+        let id = Uuid::new_v4().to_string();
+        self.conn.execute(
+            "INSERT INTO wellbeing_metrics (id, name, kind, unit) VALUES (?, ?, ?, ?)",
+            params![id, input.name, input.kind, input.unit],
+        )?;
+        Ok(id)
+    }
+
+    pub fn update_metric(&self, id: &str, input: &WellbeingMetricInput) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE wellbeing_metrics SET name = ?, kind = ?, unit = ? WHERE id = ?",
+            params![input.name, input.kind, input.unit, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_metric(&self, id: &str) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "DELETE FROM wellbeing_metric_values WHERE metric_id = ?",
+            params![id],
+        )?;
+        self.conn
+            .execute("DELETE FROM wellbeing_metrics WHERE id = ?", params![id])?;
+        Ok(())
+    }
+
+    /// Sets the metric's value for the day, or clears it with `None`.
+    pub fn set_metric_value(
+        &self,
+        metric_id: &str,
+        date: &str,
+        value: Option<f64>,
+    ) -> rusqlite::Result<()> {
+        match value {
+            Some(value) => self.conn.execute(
+                "INSERT INTO wellbeing_metric_values (metric_id, entry_date, value)
+                 VALUES (?1, ?2, ?3)
+                 ON CONFLICT(metric_id, entry_date) DO UPDATE SET value = ?3",
+                params![metric_id, date, value],
+            )?,
+            None => self.conn.execute(
+                "DELETE FROM wellbeing_metric_values WHERE metric_id = ? AND entry_date = ?",
+                params![metric_id, date],
+            )?,
+        };
+        Ok(())
+    }
+
+    /// Values of every metric, or of just one, between the two dates.
+    pub fn metric_values_between(
+        &self,
+        start_date: &str,
+        end_date: &str,
+        metric_id: Option<&str>,
+    ) -> rusqlite::Result<Vec<MetricValue>> {
+        self.conn
+            .prepare(
+                "SELECT v.metric_id, v.entry_date, v.value
+                 FROM wellbeing_metric_values v
+                 JOIN wellbeing_metrics m ON m.id = v.metric_id
+                 WHERE v.entry_date BETWEEN ?1 AND ?2 AND (?3 IS NULL OR v.metric_id = ?3)
+                 ORDER BY v.entry_date, m.created_at, m.name",
+            )?
+            .query_map(params![start_date, end_date, metric_id], |row| {
+                Ok(MetricValue {
+                    metric_id: row.get(0)?,
+                    entry_date: row.get(1)?,
+                    value: row.get(2)?,
+                })
+            })?
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::fixtures::connection;
+    use crate::database::Mood;
+
+    fn check_in(date: &str, mood: Option<Mood>, energy: Option<i32>) -> WellbeingCheckInInput {
+        WellbeingCheckInInput {
+            entry_date: date.parse().unwrap(),
+            recorded_at: None,
+            mood,
+            energy,
+            stress: None,
+            note: None,
+        }
+    }
+
+    #[test]
+    fn check_ins_come_back_in_time_order() {
+        let conn = connection();
+        let wellbeing = WellbeingRepository::new(&conn);
+
+        let evening = check_in("2026-05-01", Some(Mood::Bad), Some(2));
+        wellbeing
+            .insert_check_in(&evening, "2026-05-01T19:00:00Z")
+            .unwrap();
+        let morning = check_in("2026-05-01", Some(Mood::Good), Some(4));
+        wellbeing
+            .insert_check_in(&morning, "2026-05-01T08:00:00Z")
+            .unwrap();
+        let energy_only = check_in("2026-05-01", None, Some(3));
+        wellbeing
+            .insert_check_in(&energy_only, "2026-05-01T21:00:00Z")
+            .unwrap();
+
+        let day = wellbeing
+            .check_ins_between("2026-05-01", "2026-05-01")
+            .unwrap();
+        let moods: Vec<Option<Mood>> = day.iter().map(|check_in| check_in.mood).collect();
+        assert_eq!(moods, [Some(Mood::Good), Some(Mood::Bad), None]);
+
+        // Scores outside 1 to 5 are refused by the table
+        let wild = check_in("2026-05-01", None, Some(9));
+        assert!(wellbeing
+            .insert_check_in(&wild, "2026-05-01T22:00:00Z")
+            .is_err());
+    }
+
+    #[test]
+    fn sleep_and_metrics_are_set_per_day() {
+        let conn = connection();
+        let wellbeing = WellbeingRepository::new(&conn);
+
+        wellbeing.set_sleep("2026-05-01", Some(7.5)).unwrap();
+        wellbeing.set_sleep("2026-05-01", Some(6.0)).unwrap();
+        wellbeing.set_sleep("2026-05-02", Some(8.0)).unwrap();
+        wellbeing.set_sleep("2026-05-02", None).unwrap();
+        assert_eq!(
+            wellbeing.sleep_between("2026-05-01", "2026-05-31").unwrap(),
+            [("2026-05-01".to_string(), 6.0)]
+        );
+
+        let meditated = wellbeing
+            .insert_metric(&WellbeingMetricInput {
+                name: "Meditated".to_string(),
+                kind: MetricKind::Boolean,
+                unit: None,
+            })
+            .unwrap();
+        let coffee = wellbeing
+            .insert_metric(&WellbeingMetricInput {
+                name: "Coffee".to_string(),
+                kind: MetricKind::Number,
+                unit: Some("cups".to_string()),
+            })
+            .unwrap();
+        wellbeing
+            .set_metric_value(&meditated, "2026-05-01", Some(1.0))
+            .unwrap();
+        wellbeing
+            .set_metric_value(&coffee, "2026-05-01", Some(3.0))
+            .unwrap();
+        wellbeing
+            .set_metric_value(&coffee, "2026-05-02", Some(2.0))
+            .unwrap();
+
+        let values = wellbeing
+            .metric_values_between("2026-05-01", "2026-05-31", None)
+            .unwrap();
+        assert_eq!(values.len(), 3);
+        let coffee_values = wellbeing
+            .metric_values_between("2026-05-01", "2026-05-31", Some(&coffee))
+            .unwrap();
+        assert_eq!(coffee_values[1].value, 2.0);
+
+        wellbeing.delete_metric(&coffee).unwrap();
+        assert_eq!(wellbeing.metrics().unwrap().len(), 1);
+        let values = wellbeing
+            .metric_values_between("2026-05-01", "2026-05-31", None)
+            .unwrap();
+        assert_eq!(values.len(), 1);
+        assert_eq!(
+            wellbeing.metric(&meditated).unwrap().kind,
+            MetricKind::Boolean
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use crate::database::{
+    DailyGoal, FocusPhaseChange, FocusSession, Habit, HabitCheckIn, JournalEntry, PeriodGoal, Task,
+    TimeEntry, WellbeingDay, WellbeingMetric,
+};
+use crate::interop::ImportReport;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+/// A change to stored data, broadcast to every window so views and caches
+/// can update in place instead of polling. The payload is the row as it is
+/// now, or just its id once it's gone.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum Change {
+    TaskCreated(Task),
+    TaskUpdated(Task),
+    TaskToggled(Task),
+    TaskDeleted {
+        id: String,
+    },
+    /// Open dependents that a completed task no longer holds up
+    TasksUnblocked(Vec<Task>),
+    DependencyAdded {
+        task_id: String,
+        depends_on_id: String,
+    },
+    DependencyRemoved {
+        task_id: String,
+        depends_on_id: String,
+    },
+    GoalSaved(DailyGoal),
+    GoalToggled(DailyGoal),
+    GoalDeleted {
+        id: String,
+    },
+    /// The day's goals in their new order
+    GoalsReordered {
+        date: String,
+        goals: Vec<DailyGoal>,
+    },
+    PeriodGoalSaved(PeriodGoal),
+    PeriodGoalToggled(PeriodGoal),
+    /// Progress moved because a linked daily goal changed
+    PeriodGoalUpdated(PeriodGoal),
+    PeriodGoalDeleted {
+        id: String,
+    },
+    HabitSaved(Habit),
+    HabitDeleted {
+        id: String,
+    },
+    HabitCheckedIn(HabitCheckIn),
+    HabitCheckInRemoved {
+        habit_id: String,
+        checkin_date: String,
+    },
+    EntrySaved(JournalEntry),
+    EntryDeleted {
+        id: String,
+    },
+    /// The day as it is now, after a check-in, sleep or metric changed
+    WellbeingUpdated(WellbeingDay),
+    WellbeingMetricSaved(WellbeingMetric),
+    WellbeingMetricDeleted {
+        id: String,
+    },
+    TimerStarted(TimeEntry),
+    TimerStopped(TimeEntry),
+    FocusStarted(FocusSession),
+    FocusStopped(FocusSession),
+    FocusPhase(FocusPhaseChange),
+    /// Too many rows to send one by one; windows should refetch
+    DataImported(ImportReport),
+    SettingChanged {
+        key: String,
+        value: String,
+    },
+}
+
+impl Change {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Change::TaskCreated(_) => "task:created",
+            Change::TaskUpdated(_) => "task:updated",
+            Change::TaskToggled(_) => "task:toggled",
+            Change::TaskDeleted { .. } => "task:deleted",
+            Change::TasksUnblocked(_) => "task:unblocked",
+            Change::DependencyAdded { .. } => "dependency:added",
+            Change::DependencyRemoved { .. } => "dependency:removed",
+            Change::GoalSaved(_) => "goal:saved",
+            Change::GoalToggled(_) => "goal:toggled",
+            Change::GoalDeleted { .. } => "goal:deleted",
+            Change::GoalsReordered { .. } => "goal:reordered",
+            Change::PeriodGoalSaved(_) => "period_goal:saved",
+            Change::PeriodGoalToggled(_) => "period_goal:toggled",
+            Change::PeriodGoalUpdated(_) => "period_goal:updated",
+            Change::PeriodGoalDeleted { .. } => "period_goal:deleted",
+            Change::HabitSaved(_) => "habit:saved",
+            Change::HabitDeleted { .. } => "habit:deleted",
+            Change::HabitCheckedIn(_) => "habit:checked_in",
+            Change::HabitCheckInRemoved { .. } => "habit:check_in_removed",
+            Change::EntrySaved(_) => "entry:saved",
+            Change::EntryDeleted { .. } => "entry:deleted",
+            Change::WellbeingUpdated(_) => "wellbeing:updated",
+            Change::WellbeingMetricSaved(_) => "wellbeing_metric:saved",
+            Change::WellbeingMetricDeleted { .. } => "wellbeing_metric:deleted",
+            Change::TimerStarted(_) => "timer:started",
+            Change::TimerStopped(_) => "timer:stopped",
+            Change::FocusStarted(_) => "focus:started",
+            Change::FocusStopped(_) => "focus:stopped",
+            Change::FocusPhase(_) => "focus:phase",
+            Change::DataImported(_) => "data:imported",
+            Change::SettingChanged { .. } => "setting:changed",
+        }
+    }
+
+    /// The change is already saved, so a window that can't be reached
+    /// doesn't make the mutation fail.
+    pub fn emit(&self, app: &AppHandle) {
+        let _ = app.emit(self.name(), self);
+    }
+}
@@ -0,0 +1,150 @@
+use super::{ImportReport, ImportedEntry};
+use chrono::{DateTime, Local, Utc};
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct DayOneExport {
+    entries: Vec<DayOneEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DayOneEntry {
+    uuid: Option<String>,
+    creation_date: Option<String>,
+    time_zone: Option<String>,
+    text: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    starred: bool,
+}
+
+/// Parses a Day One JSON export (`Journal.json` inside the export zip).
+///
+/// Each entry is placed on the calendar day it was written in its own time
+/// zone. Tags and the starred flag are kept as a trailing line of the entry.
+pub fn parse(json: &str, report: &mut ImportReport) -> Result<Vec<ImportedEntry>, String> {
+    let export: DayOneExport =
+        serde_json::from_str(json).map_err(|e| format!("Invalid Day One export: {}", e))?;
+
+    let mut entries = Vec::new();
+
+    for (index, raw) in export.entries.into_iter().enumerate() {
+        let source = raw
+            .uuid
+            .clone()
+            .unwrap_or_else(|| format!("entry #{}", index + 1));
+
+        let text = raw.text.as_deref().unwrap_or("").trim().to_string();
+        if text.is_empty() {
+            report.skip(source, "entry has no text");
+            continue;
+        }
+
+        let created = match raw
+            .creation_date
+            .as_deref()
+            .map(DateTime::parse_from_rfc3339)
+        {
+            Some(Ok(dt)) => dt.with_timezone(&Utc),
+            Some(Err(e)) => {
+                report.skip(source, format!("invalid creationDate: {}", e));
+                continue;
+            }
+            None => {
+                report.skip(source, "entry has no creationDate");
+                continue;
+            }
+        };
+
+        let local = match raw.time_zone.as_deref().map(str::parse::<Tz>) {
+            Some(Ok(tz)) => created.with_timezone(&tz).naive_local(),
+            // Unknown or missing zone: fall back to this machine's zone
+            _ => created.with_timezone(&Local).naive_local(),
+        };
+
+        let mut content = text;
+        let mut footer: Vec<String> = Vec::new();
+        if raw.starred {
+            footer.push("★".to_string());
+        }
+        footer.extend(raw.tags.iter().map(|t| format!("#{}", t.replace(' ', "-"))));
+        if !footer.is_empty() {
+            content.push_str("\n\n");
+            content.push_str(&footer.join(" "));
+        }
+
+        entries.push(ImportedEntry {
+            source,
+            entry_date: local.date(),
+            created_at: Some(created),
+            content,
+            mood: None,
+        });
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_land_on_their_own_local_day() {
+        let json = r#"{"entries": [
+            {
+                "uuid": "A1",
+                "creationDate": "2026-03-01T23:30:00Z",
+                "timeZone": "Asia/Tokyo",
+                "text": "  Ramen  ",
+                "tags": ["food", "night out"],
+                "starred": true
+            },
+            {
+                "uuid": "B2",
+                "creationDate": "2026-03-01T10:00:00Z",
+                "timeZone": "America/New_York",
+                "text": "Plain"
+            }
+        ]}"#;
+        let mut report = ImportReport::default();
+        let entries = parse(json, &mut report).unwrap();
+
+        assert!(report.skipped.is_empty());
+        assert_eq!(entries[0].source, "A1");
+        assert_eq!(entries[0].entry_date.to_string(), "2026-03-02");
+        assert_eq!(
+            entries[0].created_at.unwrap().to_rfc3339(),
+            "2026-03-01T23:30:00+00:00"
+        );
+        assert_eq!(entries[0].content, "Ramen\n\n★ #food #night-out");
+        assert_eq!(entries[1].entry_date.to_string(), "2026-03-01");
+        assert_eq!(entries[1].content, "Plain");
+    }
+
+    #[test]
+    fn unusable_entries_are_skipped_and_reported() {
+        let json = r#"{"entries": [
+            {"uuid": "empty", "creationDate": "2026-03-01T10:00:00Z", "text": "   "},
+            {"uuid": "bad-date", "creationDate": "yesterday", "text": "Hi"},
+            {"text": "No date"}
+        ]}"#;
+        let mut report = ImportReport::default();
+        assert!(parse(json, &mut report).unwrap().is_empty());
+
+        let skipped: Vec<(&str, &str)> = report
+            .skipped
+            .iter()
+            .map(|note| (note.source.as_str(), note.reason.as_str()))
+            .collect();
+        assert_eq!(skipped[0], ("empty", "entry has no text"));
+        assert_eq!(skipped[1].0, "bad-date");
+        assert!(skipped[1].1.starts_with("invalid creationDate"));
+        assert_eq!(skipped[2], ("entry #3", "entry has no creationDate"));
+
+        assert!(parse("[]", &mut report).is_err());
+    }
+}
@@ -0,0 +1,180 @@
+use super::{ImportReport, ImportedEntry};
+use chrono::NaiveDate;
+use std::fs;
+use std::path::Path;
+
+/// Reads a folder holding one Markdown file per day, named after the date
+/// (`2024-03-01.md`, `2024-03-01 Friday.md`, ...).
+///
+/// An optional front matter block may set the mood:
+///
+/// ```text
+/// ---
+/// mood: good
+/// ---
+/// ```
+pub fn parse_folder(dir: &Path, report: &mut ImportReport) -> Result<Vec<ImportedEntry>, String> {
+    let read_dir =
+        fs::read_dir(dir).map_err(|e| format!("Cannot read {}: {}", dir.display(), e))?;
+
+    let mut paths: Vec<_> = read_dir
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+
+    let mut entries = Vec::new();
+
+    for path in paths {
+        let source = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let is_markdown = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown")
+            });
+        if !is_markdown {
+            report.skip(source, "not a Markdown file");
+            continue;
+        }
+
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        let entry_date = match stem
+            .get(..10)
+            .and_then(|prefix| NaiveDate::parse_from_str(prefix, "%Y-%m-%d").ok())
+        {
+            Some(date) => date,
+            None => {
+                report.skip(source, "file name does not start with a YYYY-MM-DD date");
+                continue;
+            }
+        };
+
+        let raw = match fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(e) => {
+                report.skip(source, format!("cannot read file: {}", e));
+                continue;
+            }
+        };
+
+        let (mood, body) = split_front_matter(&raw);
+        let content = body.trim().to_string();
+        if content.is_empty() {
+            report.skip(source, "file is empty");
+            continue;
+        }
+
+        entries.push(ImportedEntry {
+            source,
+            entry_date,
+            created_at: None,
+            content,
+            mood,
+        });
+    }
+
+    Ok(entries)
+}
+
+fn split_front_matter(raw: &str) -> (Option<String>, &str) {
+    let Some(rest) = raw
+        .strip_prefix("---\n")
+        .or_else(|| raw.strip_prefix("---\r\n"))
+    else {
+        return (None, raw);
+    };
+    let Some(end) = rest.find("\n---") else {
+        return (None, raw);
+    };
+
+    let mood = rest[..end].lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        let value = value.trim();
+        (key.trim() == "mood" && !value.is_empty()).then(|| value.to_string())
+    });
+
+    let body = &rest[end + "\n---".len()..];
+    (mood, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn front_matter_sets_the_mood() {
+        let (mood, body) = split_front_matter("---\nmood: good\ntags: x\n---\nDear diary");
+        assert_eq!(mood.as_deref(), Some("good"));
+        assert_eq!(body, "\nDear diary");
+
+        let (mood, _) = split_front_matter("---\r\nmood:\r\n---\r\nText");
+        assert_eq!(mood, None);
+
+        // Unclosed or missing blocks are part of the text
+        let raw = "---\nmood: good\nNo closing line";
+        assert_eq!(split_front_matter(raw), (None, raw));
+        assert_eq!(split_front_matter("Just text"), (None, "Just text"));
+    }
+
+    #[test]
+    fn folder_files_become_dated_entries() {
+        let dir = std::env::temp_dir().join(format!("journal-md-{}", uuid::Uuid::new_v4()));
+        fs::create_dir(&dir).unwrap();
+        for (name, text) in [
+            (
+                "2026-03-02 Monday.md",
+                "---\nmood: okay\n---\n\nSecond day\n",
+            ),
+            ("2026-03-01.markdown", "First day"),
+            ("2026-03-03.md", "---\nmood: bad\n---\n  \n"),
+            ("notes.md", "Undated"),
+            ("2026-03-04.txt", "Not Markdown"),
+        ] {
+            fs::write(dir.join(name), text).unwrap();
+        }
+
+        let mut report = ImportReport::default();
+        let entries = parse_folder(&dir, &mut report).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let read: Vec<(String, &str, Option<&str>)> = entries
+            .iter()
+            .map(|e| {
+                (
+                    e.entry_date.to_string(),
+                    e.content.as_str(),
+                    e.mood.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            read,
+            [
+                ("2026-03-01".to_string(), "First day", None),
+                ("2026-03-02".to_string(), "Second day", Some("okay")),
+            ]
+        );
+
+        let skipped: Vec<(&str, &str)> = report
+            .skipped
+            .iter()
+            .map(|note| (note.source.as_str(), note.reason.as_str()))
+            .collect();
+        assert_eq!(
+            skipped,
+            [
+                ("2026-03-03.md", "file is empty"),
+                ("2026-03-04.txt", "not a Markdown file"),
+                (
+                    "notes.md",
+                    "file name does not start with a YYYY-MM-DD date"
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,148 @@
+pub mod day_one;
+pub mod markdown;
+pub mod todo_txt;
+
+use crate::database::JournalRepository;
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use rusqlite::Connection;
+use serde::Serialize;
+
+/// A journal entry read from an external source.
+#[derive(Debug, Clone)]
+pub struct ImportedEntry {
+    pub source: String,
+    pub entry_date: NaiveDate,
+    /// When it was written, if the source says
+    pub created_at: Option<DateTime<Utc>>,
+    pub content: String,
+    pub mood: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ImportNote {
+    pub source: String,
+    pub entry_date: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Default, Clone)]
+pub struct ImportReport {
+    pub imported: usize,
+    /// Entries left out because the journal already has them
+    pub duplicates: Vec<ImportNote>,
+    pub skipped: Vec<ImportNote>,
+}
+
+impl ImportReport {
+    pub fn skip(&mut self, source: impl Into<String>, reason: impl Into<String>) {
+        self.skipped.push(ImportNote {
+            source: source.into(),
+            entry_date: None,
+            reason: reason.into(),
+        });
+    }
+}
+
+/// Writes imported entries into `journal_entries`, each as its own entry
+/// next to any already stored for the day. An entry with the same day, time
+/// and text as one already stored is left out, so importing the same export
+/// twice doesn't double the journal.
+pub fn save_entries(
+    conn: &mut Connection,
+    mut entries: Vec<ImportedEntry>,
+    report: &mut ImportReport,
+) -> Result<(), String> {
+    entries.sort_by_key(|e| (e.entry_date, e.created_at));
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let journal = JournalRepository::new(&tx);
+
+    for entry in entries {
+        let entry_date = entry.entry_date.format("%Y-%m-%d").to_string();
+        let created_at = entry
+            .created_at
+            .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true));
+
+        if journal
+            .contains(&entry_date, created_at.as_deref(), &entry.content)
+            .map_err(|e| e.to_string())?
+        {
+            report.duplicates.push(ImportNote {
+                source: entry.source,
+                entry_date: Some(entry_date),
+                reason: "already in the journal".to_string(),
+            });
+            continue;
+        }
+
+        journal
+            .insert(
+                &entry_date,
+                None,
+                &entry.content,
+                entry.mood.as_deref(),
+                created_at.as_deref(),
+            )
+            .map_err(|e| e.to_string())?;
+        report.imported += 1;
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::fixtures;
+
+    fn entry(source: &str, date: &str, time: Option<&str>, content: &str) -> ImportedEntry {
+        ImportedEntry {
+            source: source.to_string(),
+            entry_date: date.parse().unwrap(),
+            created_at: time.map(|time| time.parse().unwrap()),
+            content: content.to_string(),
+            mood: None,
+        }
+    }
+
+    #[test]
+    fn importing_twice_skips_what_is_already_there() {
+        let mut conn = fixtures::connection();
+        let export = || {
+            vec![
+                entry("a", "2026-03-01", Some("2026-03-01T20:00:00Z"), "Evening"),
+                entry("b", "2026-03-01", Some("2026-03-01T08:00:00Z"), "Morning"),
+                entry("c", "2026-03-02", None, "Undated"),
+            ]
+        };
+
+        let mut first = ImportReport::default();
+        save_entries(&mut conn, export(), &mut first).unwrap();
+        assert_eq!((first.imported, first.duplicates.len()), (3, 0));
+
+        let mut again = export();
+        again.push(entry(
+            "d",
+            "2026-03-01",
+            Some("2026-03-01T21:00:00Z"),
+            "Evening",
+        ));
+        let mut second = ImportReport::default();
+        save_entries(&mut conn, again, &mut second).unwrap();
+        assert_eq!(second.imported, 1);
+        let duplicates: Vec<&str> = second
+            .duplicates
+            .iter()
+            .map(|n| n.source.as_str())
+            .collect();
+        assert_eq!(duplicates, ["b", "a", "c"]);
+        assert_eq!(
+            second.duplicates[0].entry_date.as_deref(),
+            Some("2026-03-01")
+        );
+
+        let stored = JournalRepository::new(&conn).between(None, None).unwrap();
+        assert_eq!(stored.len(), 4);
+    }
+}
@@ -0,0 +1,352 @@
+use crate::database::{Priority, Task};
+use chrono::NaiveDate;
+
+/// One line of a todo.txt file.
+///
+/// Only the parts that map onto a `Task` are pulled out. Everything else in
+/// the description, including `+project`, `@context` and unknown `key:value`
+/// pairs, stays in `text` in its original order so it round-trips as-is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TodoItem {
+    pub completed: bool,
+    pub priority: Option<char>,
+    pub completion_date: Option<NaiveDate>,
+    pub creation_date: Option<NaiveDate>,
+    pub due: Option<NaiveDate>,
+    pub text: String,
+    /// The task's description, carried in a `desc:` tag with spaces and
+    /// line breaks percent-encoded so it fits on the line.
+    pub description: Option<String>,
+}
+
+impl TodoItem {
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.is_empty() {
+            return None;
+        }
+
+        let mut item = TodoItem {
+            completed: false,
+            priority: None,
+            completion_date: None,
+            creation_date: None,
+            due: None,
+            text: String::new(),
+            description: None,
+        };
+
+        if tokens[0] == "x" {
+            item.completed = true;
+            tokens.remove(0);
+        }
+
+        if let Some(priority) = tokens.first().and_then(|t| parse_priority(t)) {
+            item.priority = Some(priority);
+            tokens.remove(0);
+        }
+
+        let mut dates = Vec::new();
+        while dates.len() < 2 {
+            match tokens.first().and_then(|t| parse_date(t)) {
+                Some(date) => {
+                    dates.push(date);
+                    tokens.remove(0);
+                }
+                None => break,
+            }
+        }
+        // A single date on a completed item is its completion date
+        match (item.completed, dates.as_slice()) {
+            (true, [done, created]) => {
+                item.completion_date = Some(*done);
+                item.creation_date = Some(*created);
+            }
+            (true, [done]) => item.completion_date = Some(*done),
+            (false, [created, ..]) => item.creation_date = Some(*created),
+            _ => {}
+        }
+
+        let mut text = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            if let Some(date) = token.strip_prefix("due:").and_then(parse_date) {
+                item.due = Some(date);
+            } else if let Some(description) = token.strip_prefix("desc:") {
+                item.description = Some(decode(description));
+            } else if let Some(priority) = token
+                .strip_prefix("pri:")
+                .filter(|_| item.priority.is_none())
+                .and_then(|p| p.chars().next())
+                .filter(|p| p.is_ascii_uppercase())
+            {
+                item.priority = Some(priority);
+            } else {
+                text.push(token);
+            }
+        }
+        item.text = text.join(" ");
+
+        Some(item)
+    }
+
+    pub fn from_task(task: &Task) -> Self {
+        // A low task may carry the letter it was imported with, (D) to (Z)
+        let kept = task
+            .title
+            .rsplit_once(' ')
+            .and_then(|(text, tag)| Some((text, lower_priority(tag.strip_prefix("pri:")?)?)))
+            .filter(|_| task.priority == "low");
+
+        TodoItem {
+            completed: task.is_completed,
+            priority: match (task.priority.as_str(), kept) {
+                (_, Some((_, letter))) => Some(letter),
+                ("high", _) => Some('A'),
+                ("low", _) => Some('C'),
+                _ => Some('B'),
+            },
+            completion_date: task
+                .completed_at
+                .as_deref()
+                .and_then(|time| time.get(..10))
+                .and_then(parse_date),
+            creation_date: task.created_at.get(..10).and_then(parse_date),
+            due: parse_date(&task.scheduled_date),
+            text: kept
+                .map_or(task.title.as_str(), |(text, _)| text)
+                .to_string(),
+            description: task
+                .description
+                .clone()
+                .filter(|description| !description.is_empty()),
+        }
+    }
+
+    /// Maps the todo.txt priority onto the task priority levels.
+    pub fn task_priority(&self) -> Priority {
+        match self.priority {
+            Some('A') => Priority::High,
+            Some('B') | None => Priority::Medium,
+            Some(_) => Priority::Low,
+        }
+    }
+
+    /// The text as a task title. Priorities below (C) have no level of
+    /// their own, so they are kept as a `pri:` tag for `from_task` to find.
+    pub fn task_title(&self) -> String {
+        match self.priority.filter(|p| *p > 'C') {
+            Some(letter) => format!("{} pri:{}", self.text, letter),
+            None => self.text.clone(),
+        }
+    }
+
+    pub fn to_line(&self) -> String {
+        let mut parts = Vec::new();
+
+        if self.completed {
+            parts.push("x".to_string());
+            if let Some(date) = self.completion_date {
+                parts.push(date.format("%Y-%m-%d").to_string());
+            }
+        } else if let Some(priority) = self.priority {
+            parts.push(format!("({})", priority));
+        }
+
+        // A creation date without a completion date would be read back as
+        // the completion date
+        if let Some(date) = self
+            .creation_date
+            .filter(|_| !self.completed || self.completion_date.is_some())
+        {
+            parts.push(date.format("%Y-%m-%d").to_string());
+        }
+
+        if !self.text.is_empty() {
+            parts.push(self.text.clone());
+        }
+
+        if self.completed {
+            if let Some(priority) = self.priority {
+                parts.push(format!("pri:{}", priority));
+            }
+        }
+
+        if let Some(due) = self.due {
+            parts.push(format!("due:{}", due.format("%Y-%m-%d")));
+        }
+
+        if let Some(ref description) = self.description {
+            parts.push(format!("desc:{}", encode(description)));
+        }
+
+        parts.join(" ")
+    }
+}
+
+fn parse_priority(token: &str) -> Option<char> {
+    let mut chars = token.chars();
+    match (chars.next(), chars.next(), chars.next(), chars.next()) {
+        (Some('('), Some(p), Some(')'), None) if p.is_ascii_uppercase() => Some(p),
+        _ => None,
+    }
+}
+
+fn lower_priority(letter: &str) -> Option<char> {
+    let mut chars = letter.chars();
+    match (chars.next(), chars.next()) {
+        (Some(p @ 'D'..='Z'), None) => Some(p),
+        _ => None,
+    }
+}
+
+fn parse_date(token: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(token, "%Y-%m-%d").ok()
+}
+
+/// Percent-encodes what would break the tag apart: whitespace and `%`.
+fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '%' | ' ' | '\t' | '\n' | '\r' => encoded.push_str(&format!("%{:02X}", c as u32)),
+            _ => encoded.push(c),
+        }
+    }
+    encoded
+}
+
+fn decode(value: &str) -> String {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = (byte == b'%')
+            .then(|| tail.get(..2))
+            .flatten()
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::fixtures;
+
+    fn task(priority: &str, is_completed: bool, description: Option<&str>) -> Task {
+        Task {
+            title: "Call the bank +errands @phone".to_string(),
+            description: description.map(str::to_string),
+            priority: priority.to_string(),
+            is_completed,
+            ..fixtures::task()
+        }
+    }
+
+    fn date(value: &str) -> Option<NaiveDate> {
+        parse_date(value)
+    }
+
+    #[test]
+    fn lines_round_trip() {
+        for line in [
+            "(A) 2026-03-01 Call the bank +errands @phone due:2026-03-14",
+            "x 2026-03-15 2026-03-01 Call the bank pri:C due:2026-03-14",
+            "x Water the plants",
+            "Plan the trip key:value desc:Flights%20and%0Ahotels%2520off",
+        ] {
+            let item = TodoItem::parse(line).unwrap();
+            assert_eq!(item.to_line(), line);
+            assert_eq!(TodoItem::parse(&item.to_line()).unwrap(), item);
+        }
+
+        let item = TodoItem::parse("Plan the trip desc:Flights%20and%0Ahotels%2520off").unwrap();
+        assert_eq!(item.text, "Plan the trip");
+        assert_eq!(
+            item.description.as_deref(),
+            Some("Flights and\nhotels%20off")
+        );
+    }
+
+    #[test]
+    fn single_date_on_a_completed_item_is_its_completion_date() {
+        let item = TodoItem::parse("x 2026-03-15 Water the plants").unwrap();
+        assert_eq!(item.completion_date, date("2026-03-15"));
+        assert_eq!(item.creation_date, None);
+
+        let item = TodoItem::parse("2026-03-01 Water the plants").unwrap();
+        assert_eq!(item.creation_date, date("2026-03-01"));
+        assert!(TodoItem::parse("   ").is_none());
+    }
+
+    #[test]
+    fn exported_tasks_read_back_unchanged() {
+        let description = "Ask about the fee\n100% refund?";
+        for (priority, is_completed) in [("high", false), ("medium", true), ("low", true)] {
+            let task = Task {
+                completed_at: is_completed.then(|| "2026-03-15T18:20:00Z".to_string()),
+                ..task(priority, is_completed, Some(description))
+            };
+            let line = TodoItem::from_task(&task).to_line();
+            let item = TodoItem::parse(&line).unwrap();
+
+            assert_eq!(item.task_title(), task.title);
+            assert_eq!(item.description.as_deref(), Some(description));
+            assert_eq!(item.task_priority().as_str(), priority);
+            assert_eq!(item.completed, is_completed);
+            assert_eq!(item.due, date("2026-03-14"));
+            assert_eq!(
+                item.completion_date,
+                is_completed.then(|| date("2026-03-15")).flatten()
+            );
+        }
+
+        let line = TodoItem::from_task(&task("medium", false, None)).to_line();
+        assert_eq!(
+            line,
+            "(B) 2026-03-01 Call the bank +errands @phone due:2026-03-14"
+        );
+
+        // Done before completion times were recorded: no made-up date
+        let line = TodoItem::from_task(&task("high", true, None)).to_line();
+        assert_eq!(line, "x Call the bank +errands @phone pri:A due:2026-03-14");
+    }
+
+    #[test]
+    fn priorities_below_c_survive_as_a_title_tag() {
+        let item = TodoItem::parse("(F) Someday sort the garage").unwrap();
+        assert_eq!(item.task_priority(), Priority::Low);
+        assert_eq!(item.task_title(), "Someday sort the garage pri:F");
+
+        let garage = Task {
+            title: item.task_title(),
+            ..task("low", false, None)
+        };
+        let exported = TodoItem::from_task(&garage);
+        assert_eq!(exported.priority, Some('F'));
+        assert_eq!(exported.text, "Someday sort the garage");
+
+        // Only low tasks carry a letter, and only a single one below C
+        for (priority, title) in [
+            ("high", "Fix pri:F"),
+            ("low", "Fix pri:B"),
+            ("low", "Fix pri:FF"),
+        ] {
+            let task = Task {
+                title: title.to_string(),
+                ..task(priority, false, None)
+            };
+            assert_eq!(TodoItem::from_task(&task).text, title);
+        }
+    }
+}
@@ -1,6 +1,11 @@
 mod commands;
-mod database;
+pub mod database;
+mod events;
+pub mod interop;
 mod notifications;
+pub mod quick_add;
+pub mod scheduling;
+pub mod services;
 
 use database::Database;
 use notifications::start_notification_scheduler;
@@ -12,6 +17,7 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_notification::init())
         .manage(db)
+        .manage(commands::Operations::default())
         .invoke_handler(tauri::generate_handler![
             // Task commands
             commands::get_tasks_for_date_range,
@@ -19,15 +25,82 @@ pub fn run() {
             commands::update_task,
             commands::delete_task,
             commands::toggle_task_completion,
+            commands::parse_quick_add,
+            commands::quick_add_task,
+            commands::find_conflicts,
+            commands::find_slot_conflicts,
+            commands::auto_schedule_day,
+            commands::apply_schedule,
+            commands::add_task_dependency,
+            commands::remove_task_dependency,
+            commands::get_task_dependencies,
+            // Time tracking commands
+            commands::start_timer,
+            commands::stop_timer,
+            commands::get_running_timer,
+            commands::get_time_entries,
+            commands::get_time_report,
+            // Focus commands
+            commands::start_focus_session,
+            commands::stop_focus_session,
+            commands::get_focus_session,
+            commands::get_focus_stats,
             // Goal commands
             commands::get_goals_for_date,
             commands::upsert_goal,
+            commands::insert_goal,
+            commands::reorder_goals,
             commands::toggle_goal_completion,
             commands::delete_goal,
+            commands::set_goal_parent,
+            commands::link_task_to_goal,
+            commands::unlink_task_from_goal,
+            commands::get_goal_tasks,
+            commands::set_goal_auto_complete,
+            commands::carry_over_goals,
+            commands::get_goal_carry_over_policy,
+            commands::set_goal_carry_over_policy,
+            commands::get_goal_stats,
+            commands::get_goals_for_period,
+            commands::upsert_period_goal,
+            commands::toggle_period_goal_completion,
+            commands::delete_period_goal,
+            // Habit commands
+            commands::get_habits,
+            commands::get_habits_for_date,
+            commands::create_habit,
+            commands::update_habit,
+            commands::set_habit_archived,
+            commands::delete_habit,
+            commands::check_in_habit,
+            commands::remove_habit_check_in,
+            commands::get_habit_stats,
             // Journal commands
             commands::get_entry_for_date,
             commands::upsert_entry,
+            commands::list_entries,
+            commands::create_entry,
+            commands::update_entry,
             commands::delete_entry,
+            commands::get_mood_report,
+            // Wellbeing commands
+            commands::get_wellbeing,
+            commands::get_wellbeing_day,
+            commands::add_wellbeing_check_in,
+            commands::update_wellbeing_check_in,
+            commands::delete_wellbeing_check_in,
+            commands::set_sleep_hours,
+            commands::get_wellbeing_metrics,
+            commands::create_wellbeing_metric,
+            commands::update_wellbeing_metric,
+            commands::delete_wellbeing_metric,
+            commands::set_metric_value,
+            // Import/export commands
+            commands::import_day_one,
+            commands::import_markdown_folder,
+            commands::import_todo_txt,
+            commands::export_todo_txt,
+            commands::cancel_operation,
         ])
         .setup(|app| {
             // Start the notification scheduler
@@ -1,6 +1,8 @@
-use crate::database::Database;
-use chrono::{Local, NaiveTime};
-use rusqlite::params;
+use crate::database::{Database, FocusPhaseChange, Habit, TaskRepository};
+use crate::events::Change;
+use crate::scheduling::zones;
+use crate::services::{focus, goals, habits};
+use chrono::{Local, Utc};
 use std::sync::Arc;
 use tauri::{AppHandle, Manager};
 use tauri_plugin_notification::NotificationExt;
@@ -8,6 +10,7 @@ use tokio::time::{interval, Duration};
 
 pub fn start_notification_scheduler(app: AppHandle) {
     let app_handle = Arc::new(app);
+    let focus_handle = app_handle.clone();
 
     tokio::spawn(async move {
         let mut check_interval = interval(Duration::from_secs(60)); // Check every minute
@@ -15,80 +18,110 @@ pub fn start_notification_scheduler(app: AppHandle) {
         loop {
             check_interval.tick().await;
             check_and_send_notifications(&app_handle);
+            remind_habits(&app_handle);
+            carry_over_goals(&app_handle);
+        }
+    });
+
+    // Focus phases need second precision, so they get their own tick
+    tokio::spawn(async move {
+        let mut check_interval = interval(Duration::from_secs(1));
+
+        loop {
+            check_interval.tick().await;
+            advance_focus_session(&focus_handle);
         }
     });
 }
 
-fn check_and_send_notifications(app: &AppHandle) {
+fn advance_focus_session(app: &AppHandle) {
     let db = match app.try_state::<Database>() {
         Some(db) => db,
         None => return,
     };
 
-    let now = Local::now();
-    let today = now.format("%Y-%m-%d").to_string();
-    let current_time = now.format("%H:%M").to_string();
-
-    let conn = match db.conn.lock() {
-        Ok(c) => c,
-        Err(_) => return,
+    let change = match focus::advance_focus_session(&db, Utc::now()) {
+        Ok(Some(change)) => change,
+        _ => return,
     };
 
-    // Find tasks with reminders that should fire now
-    let mut stmt = match conn.prepare(
-        "SELECT id, title, scheduled_time, reminder_minutes
-         FROM tasks
-         WHERE scheduled_date = ?
-           AND scheduled_time IS NOT NULL
-           AND reminder_minutes IS NOT NULL
-           AND is_completed = 0",
-    ) {
-        Ok(s) => s,
-        Err(_) => return,
+    send_focus_notification(app, &change);
+    Change::FocusPhase(change).emit(app);
+}
+
+/// Applies the automatic goal carry-over once the day has turned.
+fn carry_over_goals(app: &AppHandle) {
+    let db = match app.try_state::<Database>() {
+        Some(db) => db,
+        None => return,
     };
 
-    let tasks: Vec<(String, String, String, i32)> = stmt
-        .query_map(params![today], |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-                row.get::<_, i32>(3)?,
-            ))
-        })
-        .ok()
-        .map(|iter| iter.filter_map(|r| r.ok()).collect())
-        .unwrap_or_default();
-
-    for (id, title, scheduled_time, reminder_minutes) in tasks {
-        if should_notify(&current_time, &scheduled_time, reminder_minutes) {
-            send_task_notification(app, &title, &scheduled_time);
+    if let Ok(carried) = goals::auto_carry_over_goals(&db, Local::now().date_naive()) {
+        for goal in carried {
+            Change::GoalSaved(goal).emit(app);
+        }
+    }
+}
 
-            // Mark notification as sent by clearing reminder (simple approach)
-            let _ = conn.execute(
-                "UPDATE tasks SET reminder_minutes = NULL WHERE id = ?",
-                params![id],
-            );
+fn remind_habits(app: &AppHandle) {
+    let db = match app.try_state::<Database>() {
+        Some(db) => db,
+        None => return,
+    };
+
+    if let Ok(due) = habits::due_habit_reminders(&db, Utc::now()) {
+        for habit in due {
+            send_habit_notification(app, &habit);
         }
     }
 }
 
-fn should_notify(current_time: &str, scheduled_time: &str, reminder_minutes: i32) -> bool {
-    let current = match NaiveTime::parse_from_str(current_time, "%H:%M") {
-        Ok(t) => t,
-        Err(_) => return false,
+fn check_and_send_notifications(app: &AppHandle) {
+    let db = match app.try_state::<Database>() {
+        Some(db) => db,
+        None => return,
     };
 
-    let scheduled = match NaiveTime::parse_from_str(scheduled_time, "%H:%M") {
-        Ok(t) => t,
-        Err(_) => return false,
+    let now = Utc::now();
+    let zone = zones::current_zone();
+
+    let conn = match db.conn() {
+        Ok(c) => c,
+        Err(_) => return,
     };
 
-    let reminder_time = scheduled - chrono::Duration::minutes(reminder_minutes as i64);
+    let tasks = TaskRepository::new(&conn);
+    let pending = match tasks.with_pending_reminders() {
+        Ok(pending) => pending,
+        Err(_) => return,
+    };
+
+    for task in pending {
+        let (Some(scheduled_time), Some(reminder_minutes)) =
+            (task.scheduled_time.as_deref(), task.reminder_minutes)
+        else {
+            continue;
+        };
+        let Some(start) = zones::start_instant(
+            &task.scheduled_date,
+            scheduled_time,
+            task.time_zone.as_deref(),
+        ) else {
+            continue;
+        };
 
-    // Check if current time is within the reminder minute
-    let diff = (current - reminder_time).num_minutes().abs();
-    diff == 0
+        if zones::reminder_due(start, reminder_minutes, now) {
+            let local_time = start.with_timezone(&zone).format("%H:%M").to_string();
+            send_task_notification(app, &task.title, &local_time);
+
+            // Mark notification as sent by clearing reminder (simple approach)
+            if tasks.clear_reminder(&task.id).is_ok() {
+                if let Ok(task) = tasks.get(&task.id) {
+                    Change::TaskUpdated(task).emit(app);
+                }
+            }
+        }
+    }
 }
 
 fn send_task_notification(app: &AppHandle, title: &str, scheduled_time: &str) {
@@ -99,3 +132,44 @@ fn send_task_notification(app: &AppHandle, title: &str, scheduled_time: &str) {
         .body(format!("{} at {}", title, scheduled_time))
         .show();
 }
+
+fn send_habit_notification(app: &AppHandle, habit: &Habit) {
+    let body = match (habit.target_value, &habit.unit) {
+        (Some(target), Some(unit)) => format!("{} ({} {})", habit.name, target, unit),
+        (Some(target), None) => format!("{} ({})", habit.name, target),
+        _ => habit.name.clone(),
+    };
+
+    let _ = app
+        .notification()
+        .builder()
+        .title("Habit Reminder")
+        .body(body)
+        .show();
+}
+
+fn send_focus_notification(app: &AppHandle, change: &FocusPhaseChange) {
+    let session = &change.session;
+    let (title, body) = match session.phase.as_str() {
+        "long_break" => (
+            "Time for a long break",
+            format!(
+                "{} focus cycles done. Rest for {} minutes.",
+                session.completed_cycles, session.long_break_minutes
+            ),
+        ),
+        "short_break" => (
+            "Time for a break",
+            format!(
+                "Focus cycle {} done. Rest for {} minutes.",
+                session.completed_cycles, session.short_break_minutes
+            ),
+        ),
+        _ => (
+            "Back to focus",
+            format!("Focus for {} minutes.", session.work_minutes),
+        ),
+    };
+
+    let _ = app.notification().builder().title(title).body(body).show();
+}
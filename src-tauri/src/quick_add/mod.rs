@@ -0,0 +1,3 @@
+pub mod parser;
+
+pub use parser::*;
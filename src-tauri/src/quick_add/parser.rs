@@ -0,0 +1,464 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use serde::Serialize;
+
+/// What `parse` understood from a quick-add line, shown to the user for
+/// confirmation before (or after) the task is created.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct QuickAdd {
+    pub title: String,
+    pub scheduled_date: String,
+    pub scheduled_time: Option<String>,
+    pub duration_minutes: Option<i32>,
+    pub priority: Option<String>,
+    pub reminder_minutes: Option<i32>,
+    pub tags: Vec<String>,
+    /// Each recognized phrase with the field it was read as, in input order.
+    pub recognized: Vec<RecognizedPhrase>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RecognizedPhrase {
+    pub field: &'static str,
+    pub text: String,
+}
+
+/// Parses text such as `Call Sam tomorrow 3pm for 45m !high remind 10m #work`.
+///
+/// Dates are resolved relative to `today`. Words that aren't recognized as a
+/// date, time, duration, priority, reminder or tag make up the title.
+pub fn parse(text: &str, today: NaiveDate) -> QuickAdd {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+
+    let mut date = None;
+    let mut result = QuickAdd {
+        title: String::new(),
+        scheduled_date: String::new(),
+        scheduled_time: None,
+        duration_minutes: None,
+        priority: None,
+        reminder_minutes: None,
+        tags: Vec::new(),
+        recognized: Vec::new(),
+    };
+    let mut title = Vec::new();
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let rest = &tokens[i..];
+        let word = normalize(rest[0]);
+
+        let matched: Option<(&'static str, usize)> = if let Some(tag) =
+            rest[0].strip_prefix('#').filter(|t| !t.is_empty())
+        {
+            result
+                .tags
+                .push(tag.trim_end_matches([',', '.']).to_string());
+            Some(("tag", 1))
+        } else if let Some(priority) = rest[0].strip_prefix('!').and_then(parse_priority) {
+            result.priority = Some(priority.to_string());
+            Some(("priority", 1))
+        } else if word == "remind" {
+            parse_reminder(&rest[1..]).map(|(minutes, used)| {
+                result.reminder_minutes = Some(minutes);
+                ("reminder", used + 1)
+            })
+        } else if let Some((minutes, used)) = result
+            .duration_minutes
+            .is_none()
+            .then(|| parse_duration(rest))
+            .flatten()
+        {
+            result.duration_minutes = Some(minutes);
+            Some(("duration", used))
+        } else if let Some((time, used)) = result
+            .scheduled_time
+            .is_none()
+            .then(|| parse_time(rest))
+            .flatten()
+        {
+            result.scheduled_time = Some(time.format("%H:%M").to_string());
+            Some(("time", used))
+        } else if let Some((day, used)) = date.is_none().then(|| parse_date(rest, today)).flatten()
+        {
+            date = Some(day);
+            Some(("date", used))
+        } else {
+            None
+        };
+
+        match matched {
+            Some((field, used)) => {
+                result.recognized.push(RecognizedPhrase {
+                    field,
+                    text: rest[..used].join(" "),
+                });
+                i += used;
+            }
+            None => {
+                title.push(rest[0]);
+                i += 1;
+            }
+        }
+    }
+
+    result.title = title.join(" ");
+    result.scheduled_date = date.unwrap_or(today).format("%Y-%m-%d").to_string();
+    result
+}
+
+fn normalize(token: &str) -> String {
+    token.trim_end_matches([',', '.']).to_lowercase()
+}
+
+fn parse_priority(value: &str) -> Option<&'static str> {
+    match value.to_lowercase().trim_end_matches([',', '.']) {
+        "high" | "h" | "1" => Some("high"),
+        "medium" | "med" | "m" | "2" => Some("medium"),
+        "low" | "l" | "3" => Some("low"),
+        _ => None,
+    }
+}
+
+/// `remind 10m`, `remind me 10 min before`. Returns minutes and tokens used
+/// after the `remind` keyword.
+fn parse_reminder(tokens: &[&str]) -> Option<(i32, usize)> {
+    let skipped = usize::from(tokens.first().is_some_and(|t| normalize(t) == "me"));
+    let (minutes, used) = parse_duration(&tokens[skipped..])?;
+    let before = usize::from(
+        tokens
+            .get(skipped + used)
+            .is_some_and(|t| normalize(t) == "before"),
+    );
+    Some((minutes, skipped + used + before))
+}
+
+/// `45m`, `1h30m`, `1.5h`, `45 min`, `for 2 hours`.
+fn parse_duration(tokens: &[&str]) -> Option<(i32, usize)> {
+    let offset = usize::from(tokens.len() > 1 && normalize(tokens[0]) == "for");
+    let first = normalize(tokens.get(offset)?);
+
+    if let Some(minutes) = parse_compact_duration(&first) {
+        return Some((minutes, offset + 1));
+    }
+
+    let amount: f64 = first.parse().ok()?;
+    let unit = normalize(tokens.get(offset + 1)?);
+    let minutes = whole_minutes(amount * unit_minutes(&unit)?)?;
+    Some((minutes, offset + 2))
+}
+
+/// Longest duration or reminder lead time taken: a week.
+const MAX_MINUTES: f64 = 7.0 * 24.0 * 60.0;
+
+/// Rounds to whole minutes, refusing anything that isn't a positive
+/// amount up to `MAX_MINUTES`.
+fn whole_minutes(minutes: f64) -> Option<i32> {
+    (minutes.is_finite() && minutes > 0.0 && minutes <= MAX_MINUTES)
+        .then(|| minutes.round() as i32)
+        .filter(|&minutes| minutes > 0)
+}
+
+fn parse_compact_duration(token: &str) -> Option<i32> {
+    let mut total = 0.0;
+    let mut rest = token;
+    while !rest.is_empty() {
+        let number_end = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .filter(|&end| end > 0)?;
+        let amount: f64 = rest[..number_end].parse().ok()?;
+        rest = &rest[number_end..];
+
+        let unit_end = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        total += amount * unit_minutes(&rest[..unit_end])?;
+        rest = &rest[unit_end..];
+    }
+    whole_minutes(total)
+}
+
+fn unit_minutes(unit: &str) -> Option<f64> {
+    match unit {
+        "m" | "min" | "mins" | "minute" | "minutes" => Some(1.0),
+        "h" | "hr" | "hrs" | "hour" | "hours" => Some(60.0),
+        _ => None,
+    }
+}
+
+/// `3pm`, `3:30pm`, `15:00`, `3 pm`, `at 9`, `noon`, `midnight`.
+fn parse_time(tokens: &[&str]) -> Option<(NaiveTime, usize)> {
+    let has_at = tokens.len() > 1 && normalize(tokens[0]) == "at";
+    let offset = usize::from(has_at);
+    let first = normalize(tokens.get(offset)?);
+
+    match first.as_str() {
+        "noon" => return Some((NaiveTime::from_hms_opt(12, 0, 0)?, offset + 1)),
+        "midnight" => return Some((NaiveTime::from_hms_opt(0, 0, 0)?, offset + 1)),
+        _ => {}
+    }
+
+    for suffix in ["am", "pm"] {
+        if let Some(clock) = first.strip_suffix(suffix) {
+            return Some((twelve_hour(clock, suffix)?, offset + 1));
+        }
+        if tokens
+            .get(offset + 1)
+            .is_some_and(|next| normalize(next) == suffix)
+        {
+            return Some((twelve_hour(&first, suffix)?, offset + 2));
+        }
+    }
+
+    if first.contains(':') {
+        return NaiveTime::parse_from_str(&first, "%H:%M")
+            .ok()
+            .map(|t| (t, offset + 1));
+    }
+
+    // A bare hour is only a time when introduced by "at"
+    if has_at {
+        let hour: u32 = first.parse().ok()?;
+        return NaiveTime::from_hms_opt(hour, 0, 0).map(|t| (t, offset + 1));
+    }
+
+    None
+}
+
+fn twelve_hour(clock: &str, suffix: &str) -> Option<NaiveTime> {
+    let (hour, minute) = match clock.split_once(':') {
+        Some((h, m)) => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        None => (clock.parse::<u32>().ok()?, 0),
+    };
+    if !(1..=12).contains(&hour) {
+        return None;
+    }
+    let hour = match (hour, suffix) {
+        (12, "am") => 0,
+        (12, "pm") => 12,
+        (h, "pm") => h + 12,
+        (h, _) => h,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// `today`, `tomorrow`, `friday`, `next friday`, `in 3 days`, `2024-05-01`,
+/// `may 5`, `5 may`, optionally introduced by `on`.
+///
+/// A bare weekday is the next day with that name, counting today; `next`
+/// picks that day in the following calendar week.
+fn parse_date(tokens: &[&str], today: NaiveDate) -> Option<(NaiveDate, usize)> {
+    let has_on = tokens.len() > 1 && normalize(tokens[0]) == "on";
+    let offset = usize::from(has_on);
+    let first = normalize(tokens.get(offset)?);
+    let second = tokens.get(offset + 1).map(|t| normalize(t));
+
+    match first.as_str() {
+        "today" | "tonight" => return Some((today, offset + 1)),
+        "tomorrow" | "tmr" => return Some((today.succ_opt()?, offset + 1)),
+        _ => {}
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(&first, "%Y-%m-%d") {
+        return Some((date, offset + 1));
+    }
+
+    if let Some(weekday) = parse_weekday(&first) {
+        return Some((next_weekday(today, weekday), offset + 1));
+    }
+
+    if first == "next" {
+        let weekday = parse_weekday(second.as_deref()?)?;
+        let next_monday = next_weekday(today + Duration::days(1), Weekday::Mon);
+        let date = next_monday + Duration::days(weekday.num_days_from_monday() as i64);
+        return Some((date, offset + 2));
+    }
+
+    if first == "in" && !has_on {
+        let amount: i64 = second?.parse().ok()?;
+        let unit = normalize(tokens.get(offset + 2)?);
+        let days = match unit.as_str() {
+            "day" | "days" => amount,
+            "week" | "weeks" => amount.checked_mul(7)?,
+            _ => return None,
+        };
+        let date = today.checked_add_signed(Duration::try_days(days)?)?;
+        return Some((date, offset + 3));
+    }
+
+    // "may 5" / "5 may"
+    let (month, day) = match (parse_month(&first), second.as_deref()) {
+        (Some(month), Some(day)) => (month, parse_day_of_month(day)?),
+        (None, Some(month)) => (parse_month(month)?, parse_day_of_month(&first)?),
+        _ => return None,
+    };
+    let this_year = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+    let date = if this_year < today {
+        NaiveDate::from_ymd_opt(today.year() + 1, month, day)?
+    } else {
+        this_year
+    };
+    Some((date, offset + 2))
+}
+
+fn next_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let ahead = (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+    today + Duration::days(ahead as i64)
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    match word {
+        "mon" | "monday" => Some(Weekday::Mon),
+        "tue" | "tues" | "tuesday" => Some(Weekday::Tue),
+        "wed" | "wednesday" => Some(Weekday::Wed),
+        "thu" | "thur" | "thurs" | "thursday" => Some(Weekday::Thu),
+        "fri" | "friday" => Some(Weekday::Fri),
+        "sat" | "saturday" => Some(Weekday::Sat),
+        "sun" | "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn parse_month(word: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+    if word.len() < 3 {
+        return None;
+    }
+    MONTHS
+        .iter()
+        .position(|month| month.starts_with(word))
+        .map(|index| index as u32 + 1)
+}
+
+fn parse_day_of_month(word: &str) -> Option<u32> {
+    let digits = word.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    digits.parse().ok().filter(|day| (1..=31).contains(day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        // A Wednesday
+        NaiveDate::from_ymd_opt(2026, 3, 11).unwrap()
+    }
+
+    fn date_of(text: &str) -> String {
+        parse(text, today()).scheduled_date
+    }
+
+    #[test]
+    fn reads_every_field_out_of_the_title() {
+        let add = parse(
+            "Call Sam tomorrow 3pm for 45m !high remind me 10 min before #work",
+            today(),
+        );
+        assert_eq!(add.title, "Call Sam");
+        assert_eq!(add.scheduled_date, "2026-03-12");
+        assert_eq!(add.scheduled_time.as_deref(), Some("15:00"));
+        assert_eq!(add.duration_minutes, Some(45));
+        assert_eq!(add.priority.as_deref(), Some("high"));
+        assert_eq!(add.reminder_minutes, Some(10));
+        assert_eq!(add.tags, ["work"]);
+
+        let fields: Vec<&str> = add.recognized.iter().map(|p| p.field).collect();
+        assert_eq!(
+            fields,
+            ["date", "time", "duration", "priority", "reminder", "tag"]
+        );
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(date_of("Pay rent"), "2026-03-11");
+        assert_eq!(date_of("Pay rent today"), "2026-03-11");
+        assert_eq!(date_of("Pay rent wednesday"), "2026-03-11");
+        assert_eq!(date_of("Pay rent on fri"), "2026-03-13");
+        assert_eq!(date_of("Pay rent next monday"), "2026-03-16");
+        assert_eq!(date_of("Pay rent in 3 days"), "2026-03-14");
+        assert_eq!(date_of("Pay rent in 2 weeks"), "2026-03-25");
+        assert_eq!(date_of("Pay rent 2026-04-01"), "2026-04-01");
+        assert_eq!(date_of("Pay rent may 5th"), "2026-05-05");
+        // Already past this year, so next year's
+        assert_eq!(date_of("Pay rent 5 jan"), "2027-01-05");
+    }
+
+    #[test]
+    fn times() {
+        let time_of = |text: &str| parse(text, today()).scheduled_time;
+        assert_eq!(time_of("Standup 9am").as_deref(), Some("09:00"));
+        assert_eq!(time_of("Standup 12am").as_deref(), Some("00:00"));
+        assert_eq!(time_of("Standup 3:30 pm").as_deref(), Some("15:30"));
+        assert_eq!(time_of("Standup 15:45").as_deref(), Some("15:45"));
+        assert_eq!(time_of("Standup at 9").as_deref(), Some("09:00"));
+        assert_eq!(time_of("Lunch noon").as_deref(), Some("12:00"));
+        // A bare number without "at" stays in the title
+        let add = parse("Read 9 chapters", today());
+        assert_eq!(add.scheduled_time, None);
+        assert_eq!(add.title, "Read 9 chapters");
+        assert_eq!(time_of("Standup 13pm"), None);
+    }
+
+    #[test]
+    fn durations() {
+        let minutes_of = |text: &str| parse(text, today()).duration_minutes;
+        assert_eq!(minutes_of("Run 45m"), Some(45));
+        assert_eq!(minutes_of("Run 1h30m"), Some(90));
+        assert_eq!(minutes_of("Run 1.5h"), Some(90));
+        assert_eq!(minutes_of("Run for 2 hours"), Some(120));
+        assert_eq!(minutes_of("Run 0m"), None);
+    }
+
+    #[test]
+    fn priorities_and_tags() {
+        let add = parse("Taxes !LOW #home, #money.", today());
+        assert_eq!(add.priority.as_deref(), Some("low"));
+        assert_eq!(add.tags, ["home", "money"]);
+        assert_eq!(add.title, "Taxes");
+
+        let add = parse("Say hi! # !urgent", today());
+        assert_eq!(add.priority, None);
+        assert!(add.tags.is_empty());
+        assert_eq!(add.title, "Say hi! # !urgent");
+    }
+
+    #[test]
+    fn out_of_range_numbers_stay_in_the_title() {
+        for text in [
+            "Wait in 99999999999 weeks",
+            "Wait in 999999999 days",
+            "Wait in 9223372036854775807 days",
+        ] {
+            let add = parse(text, today());
+            assert_eq!(add.scheduled_date, "2026-03-11");
+            assert_eq!(add.title, text);
+        }
+
+        for text in [
+            "Nap -5 min",
+            "Nap inf min",
+            "Nap NaN h",
+            "Nap 1e12 h",
+            "Nap 99999999999999h",
+            "Nap remind 1e12 h",
+        ] {
+            let add = parse(text, today());
+            assert_eq!(add.duration_minutes, None, "{}", text);
+            assert_eq!(add.reminder_minutes, None, "{}", text);
+        }
+        assert_eq!(parse("Trip 168 h", today()).duration_minutes, Some(10080));
+    }
+}
@@ -0,0 +1,173 @@
+use crate::database::{Task, TaskConflict};
+use crate::scheduling::zones;
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use chrono_tz::Tz;
+
+/// The stretch of time a timed task occupies. Tasks that run past midnight
+/// simply end on a later day.
+#[derive(Debug, Clone)]
+pub struct TimedSlot {
+    pub task_id: String,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+impl TimedSlot {
+    pub fn new(
+        task_id: &str,
+        scheduled_date: &str,
+        scheduled_time: &str,
+        duration_minutes: i32,
+    ) -> Option<Self> {
+        let date = NaiveDate::parse_from_str(scheduled_date, "%Y-%m-%d").ok()?;
+        let time = NaiveTime::parse_from_str(scheduled_time, "%H:%M").ok()?;
+        let start = date.and_time(time);
+        Some(TimedSlot {
+            task_id: task_id.to_string(),
+            start,
+            end: start + Duration::minutes(duration_minutes.max(0) as i64),
+        })
+    }
+
+    /// Untimed and completed tasks don't hold a slot. Times are seen from
+    /// the zone the app is running in.
+    pub fn from_task(task: &Task) -> Option<Self> {
+        if task.is_completed {
+            return None;
+        }
+        TimedSlot::in_zone(task, zones::current_zone())
+    }
+
+    /// The task's slot as wall-clock time in `view`, whichever zone it was
+    /// planned in. The end is worked out from the start instant, so the
+    /// duration holds across DST changes.
+    pub fn in_zone(task: &Task, view: Tz) -> Option<Self> {
+        let start = zones::start_instant(
+            &task.scheduled_date,
+            task.scheduled_time.as_deref()?,
+            task.time_zone.as_deref(),
+        )?;
+        let end = start + Duration::minutes(task.duration_minutes.max(0) as i64);
+        Some(TimedSlot {
+            task_id: task.id.clone(),
+            start: start.with_timezone(&view).naive_local(),
+            end: end.with_timezone(&view).naive_local(),
+        })
+    }
+
+    fn conflict_with(&self, other: &TimedSlot) -> Option<TaskConflict> {
+        let start = self.start.max(other.start);
+        let end = self.end.min(other.end);
+        (start < end).then(|| TaskConflict {
+            task_id: self.task_id.clone(),
+            other_task_id: other.task_id.clone(),
+            overlap_start: start.format("%Y-%m-%d %H:%M").to_string(),
+            overlap_end: end.format("%Y-%m-%d %H:%M").to_string(),
+            overlap_minutes: (end - start).num_minutes() as i32,
+        })
+    }
+}
+
+/// Every overlapping pair among `slots`, found with a sweep over start times
+/// so only slots that are still running are compared.
+pub fn find_overlaps(mut slots: Vec<TimedSlot>) -> Vec<TaskConflict> {
+    slots.sort_by_key(|slot| slot.start);
+
+    let mut conflicts = Vec::new();
+    let mut active: Vec<&TimedSlot> = Vec::new();
+
+    for slot in &slots {
+        active.retain(|running| running.end > slot.start);
+        conflicts.extend(
+            active
+                .iter()
+                .filter_map(|running| running.conflict_with(slot)),
+        );
+        active.push(slot);
+    }
+
+    conflicts
+}
+
+/// Overlaps between one (possibly not yet saved) slot and the given slots.
+pub fn overlaps_with(slot: &TimedSlot, others: &[TimedSlot]) -> Vec<TaskConflict> {
+    others
+        .iter()
+        .filter(|other| other.task_id != slot.task_id)
+        .filter_map(|other| slot.conflict_with(other))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(id: &str, date: &str, time: &str, minutes: i32) -> TimedSlot {
+        TimedSlot::new(id, date, time, minutes).unwrap()
+    }
+
+    fn pairs(conflicts: &[TaskConflict]) -> Vec<(&str, &str, i32)> {
+        conflicts
+            .iter()
+            .map(|c| {
+                (
+                    c.task_id.as_str(),
+                    c.other_task_id.as_str(),
+                    c.overlap_minutes,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn touching_tasks_do_not_overlap() {
+        let conflicts = find_overlaps(vec![
+            slot("a", "2026-03-11", "09:00", 60),
+            slot("b", "2026-03-11", "10:00", 30),
+            slot("c", "2026-03-11", "10:30", 0),
+        ]);
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn nested_task_overlaps_for_its_whole_length() {
+        let conflicts = find_overlaps(vec![
+            slot("inner", "2026-03-11", "10:00", 30),
+            slot("outer", "2026-03-11", "09:00", 180),
+        ]);
+        assert_eq!(pairs(&conflicts), [("outer", "inner", 30)]);
+        assert_eq!(conflicts[0].overlap_start, "2026-03-11 10:00");
+        assert_eq!(conflicts[0].overlap_end, "2026-03-11 10:30");
+    }
+
+    #[test]
+    fn tasks_running_past_midnight_overlap_the_next_day() {
+        let conflicts = find_overlaps(vec![
+            slot("early", "2026-03-12", "00:30", 60),
+            slot("late", "2026-03-11", "23:00", 120),
+        ]);
+        assert_eq!(pairs(&conflicts), [("late", "early", 30)]);
+        assert_eq!(conflicts[0].overlap_start, "2026-03-12 00:30");
+        assert_eq!(conflicts[0].overlap_end, "2026-03-12 01:00");
+
+        let later = find_overlaps(vec![
+            slot("late", "2026-03-11", "23:00", 120),
+            slot("breakfast", "2026-03-12", "08:00", 30),
+        ]);
+        assert!(later.is_empty());
+    }
+
+    #[test]
+    fn three_way_overlap_reports_every_pair() {
+        let conflicts = find_overlaps(vec![
+            slot("a", "2026-03-11", "09:00", 90),
+            slot("b", "2026-03-11", "09:30", 60),
+            slot("c", "2026-03-11", "10:00", 60),
+            slot("d", "2026-03-11", "11:00", 30),
+        ]);
+        assert_eq!(
+            pairs(&conflicts),
+            [("a", "b", 60), ("a", "c", 30), ("b", "c", 30)]
+        );
+    }
+}
@@ -0,0 +1,6 @@
+pub mod conflicts;
+pub mod planner;
+pub mod zones;
+
+pub use conflicts::*;
+pub use planner::*;
@@ -0,0 +1,249 @@
+use chrono::{NaiveTime, Timelike};
+
+/// An untimed task waiting for a slot.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub task_id: String,
+    pub duration_minutes: i32,
+    pub priority: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Placement {
+    /// Start of the slot in minutes after midnight.
+    Placed {
+        task_id: String,
+        start_minute: i32,
+    },
+    Unplaced {
+        task_id: String,
+        reason: String,
+    },
+}
+
+/// Minutes after midnight.
+pub fn minute_of_day(time: NaiveTime) -> i32 {
+    (time.hour() * 60 + time.minute()) as i32
+}
+
+/// The time `minute` minutes after midnight, if that is still the same day.
+pub fn time_of_minute(minute: i32) -> Option<NaiveTime> {
+    let minute = u32::try_from(minute).ok()?;
+    NaiveTime::from_hms_opt(minute / 60, minute % 60, 0)
+}
+
+fn priority_rank(priority: &str) -> u8 {
+    match priority {
+        "high" => 0,
+        "medium" => 1,
+        _ => 2,
+    }
+}
+
+/// Places candidates into the free time between `day_start` and `day_end`
+/// (minutes after midnight), first-fit in priority order with the longest
+/// tasks first within a priority. Ties go to the task created first, so the
+/// same day always gets the same plan.
+///
+/// `busy` holds existing tasks and breaks. Every placed task keeps
+/// `buffer_minutes` clear on both sides of any other busy interval, except
+/// breaks, which are taken as already including their own margin.
+pub fn plan_day(
+    day_start: i32,
+    day_end: i32,
+    busy: &[(i32, i32)],
+    breaks: &[(i32, i32)],
+    buffer_minutes: i32,
+    mut candidates: Vec<Candidate>,
+) -> Vec<Placement> {
+    candidates.sort_by(|a, b| {
+        priority_rank(&a.priority)
+            .cmp(&priority_rank(&b.priority))
+            .then(b.duration_minutes.cmp(&a.duration_minutes))
+            .then_with(|| a.created_at.cmp(&b.created_at))
+            .then_with(|| a.task_id.cmp(&b.task_id))
+    });
+
+    let mut blocked: Vec<(i32, i32)> = busy
+        .iter()
+        .map(|&(start, end)| (start - buffer_minutes, end + buffer_minutes))
+        .chain(breaks.iter().copied())
+        .collect();
+
+    let mut placements = Vec::with_capacity(candidates.len());
+
+    for candidate in candidates {
+        let duration = candidate.duration_minutes.max(1);
+
+        match earliest_gap(day_start, day_end, &blocked, duration) {
+            Some(start) => {
+                blocked.push((start - buffer_minutes, start + duration + buffer_minutes));
+                placements.push(Placement::Placed {
+                    task_id: candidate.task_id,
+                    start_minute: start,
+                });
+            }
+            None => placements.push(Placement::Unplaced {
+                task_id: candidate.task_id,
+                reason: format!("no free slot of {} minutes", duration),
+            }),
+        }
+    }
+
+    placements
+}
+
+fn earliest_gap(
+    day_start: i32,
+    day_end: i32,
+    blocked: &[(i32, i32)],
+    duration: i32,
+) -> Option<i32> {
+    let mut sorted = blocked.to_vec();
+    sorted.sort();
+
+    let mut cursor = day_start;
+    for (start, end) in sorted {
+        if start - cursor >= duration {
+            break;
+        }
+        cursor = cursor.max(end);
+    }
+
+    (cursor + duration <= day_end).then_some(cursor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: &str, duration_minutes: i32, priority: &str) -> Candidate {
+        Candidate {
+            task_id: id.to_string(),
+            duration_minutes,
+            priority: priority.to_string(),
+            created_at: "2026-03-01 09:00:00".to_string(),
+        }
+    }
+
+    fn at(time: &str) -> i32 {
+        minute_of_day(time.parse().unwrap())
+    }
+
+    fn placed(id: &str, start: &str) -> Placement {
+        Placement::Placed {
+            task_id: id.to_string(),
+            start_minute: at(start),
+        }
+    }
+
+    #[test]
+    fn fills_gaps_around_busy_time_with_a_buffer() {
+        let nine = at("09:00");
+        let five = at("17:00");
+        let busy = [(at("10:00"), at("11:00"))];
+
+        let plan = plan_day(
+            nine,
+            five,
+            &busy,
+            &[],
+            5,
+            vec![
+                candidate("short", 30, "low"),
+                candidate("long", 60, "high"),
+                candidate("fits-before", 50, "medium"),
+            ],
+        );
+        assert_eq!(
+            plan,
+            [
+                // 09:00-10:00 is only 55 minutes once the buffer is taken
+                placed("long", "11:05"),
+                placed("fits-before", "09:00"),
+                placed("short", "12:10"),
+            ]
+        );
+    }
+
+    #[test]
+    fn breaks_block_time_without_a_buffer() {
+        let breaks = [(at("12:00"), at("13:00"))];
+        let plan = plan_day(
+            at("11:00"),
+            at("14:00"),
+            &[],
+            &breaks,
+            15,
+            vec![candidate("a", 60, "high"), candidate("b", 60, "high")],
+        );
+        assert_eq!(plan, [placed("a", "11:00"), placed("b", "13:00")]);
+    }
+
+    #[test]
+    fn tasks_that_do_not_fit_the_work_window_are_unplaced() {
+        let plan = plan_day(
+            at("09:00"),
+            at("10:00"),
+            &[],
+            &[],
+            0,
+            vec![
+                candidate("a", 40, "medium"),
+                candidate("b", 30, "medium"),
+                candidate("c", 20, "medium"),
+                candidate("huge", 120, "low"),
+            ],
+        );
+        assert_eq!(
+            plan,
+            [
+                placed("a", "09:00"),
+                Placement::Unplaced {
+                    task_id: "b".to_string(),
+                    reason: "no free slot of 30 minutes".to_string(),
+                },
+                placed("c", "09:40"),
+                Placement::Unplaced {
+                    task_id: "huge".to_string(),
+                    reason: "no free slot of 120 minutes".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn equal_tasks_are_planned_in_creation_order() {
+        let created = |id: &str, created_at: &str| Candidate {
+            created_at: created_at.to_string(),
+            ..candidate(id, 30, "medium")
+        };
+        let tasks = vec![
+            created("b", "2026-03-01 10:00:00"),
+            created("c", "2026-03-01 09:00:00"),
+            created("a", "2026-03-01 10:00:00"),
+        ];
+        let mut reversed = tasks.clone();
+        reversed.reverse();
+
+        let expected = [
+            placed("c", "09:00"),
+            placed("a", "09:30"),
+            placed("b", "10:00"),
+        ];
+        for candidates in [tasks, reversed] {
+            let plan = plan_day(540, 1020, &[], &[], 0, candidates);
+            assert_eq!(plan, expected);
+        }
+    }
+
+    #[test]
+    fn gaps_are_found_between_overlapping_blocks() {
+        let blocked = [(600, 660), (540, 620), (700, 720)];
+        assert_eq!(earliest_gap(540, 1020, &blocked, 40), Some(660));
+        assert_eq!(earliest_gap(540, 1020, &blocked, 41), Some(720));
+        assert_eq!(earliest_gap(540, 760, &blocked, 41), None);
+        assert_eq!(earliest_gap(540, 580, &[], 40), Some(540));
+    }
+}
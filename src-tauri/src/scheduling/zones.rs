@@ -0,0 +1,179 @@
+use chrono::LocalResult;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// The zone the computer is in right now, or UTC if it can't be read.
+pub fn current_zone() -> Tz {
+    iana_time_zone::get_timezone()
+        .ok()
+        .and_then(|name| name.parse().ok())
+        .unwrap_or(Tz::UTC)
+}
+
+/// Today's date in the zone the app is running in.
+pub fn today() -> NaiveDate {
+    Utc::now().with_timezone(&current_zone()).date_naive()
+}
+
+pub fn parse_zone(name: &str) -> Result<Tz, String> {
+    name.parse()
+        .map_err(|_| format!("Unknown time zone: {}", name))
+}
+
+/// The instant a wall-clock time in `zone` refers to.
+///
+/// A time that happens twice when clocks go back means the first one. A time
+/// skipped when clocks go forward is read with the offset from before the
+/// change, so 02:30 on a 02:00 → 03:00 night lands at 03:30.
+pub fn resolve_local(local: NaiveDateTime, zone: Tz) -> Option<DateTime<Utc>> {
+    match zone.from_local_datetime(&local) {
+        LocalResult::Single(time) => Some(time.with_timezone(&Utc)),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest.with_timezone(&Utc)),
+        LocalResult::None => {
+            let before = zone
+                .from_local_datetime(&(local - Duration::hours(3)))
+                .earliest()?;
+            let offset = before.offset().fix().local_minus_utc();
+            Some(Utc.from_utc_datetime(&(local - Duration::seconds(offset as i64))))
+        }
+    }
+}
+
+/// When a timed task starts. A task without a zone is taken to be in the
+/// zone the app is running in.
+pub fn start_instant(
+    scheduled_date: &str,
+    scheduled_time: &str,
+    time_zone: Option<&str>,
+) -> Option<DateTime<Utc>> {
+    let date = NaiveDate::parse_from_str(scheduled_date, "%Y-%m-%d").ok()?;
+    let time = NaiveTime::parse_from_str(scheduled_time, "%H:%M").ok()?;
+    let zone = match time_zone {
+        Some(name) => parse_zone(name).ok()?,
+        None => current_zone(),
+    };
+    resolve_local(date.and_time(time), zone)
+}
+
+/// Whether a reminder set `reminder_minutes` before `start` should go out
+/// at `now`. The window is two minutes wide so a late scheduler tick still
+/// catches it; callers clear the reminder once sent.
+pub fn reminder_due(start: DateTime<Utc>, reminder_minutes: i32, now: DateTime<Utc>) -> bool {
+    let fire_at = start - Duration::minutes(reminder_minutes as i64);
+    fire_at <= now && now < fire_at + Duration::minutes(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{fixtures, Task};
+    use crate::scheduling::TimedSlot;
+    use crate::services::tasks::task_days;
+    use chrono_tz::{America, Europe};
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn local(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn task(date: &str, time: &str, duration_minutes: i32, zone: &str) -> Task {
+        Task {
+            scheduled_date: date.to_string(),
+            scheduled_time: Some(time.to_string()),
+            time_zone: Some(zone.to_string()),
+            duration_minutes,
+            occurrence_date: date.to_string(),
+            ..fixtures::task()
+        }
+    }
+
+    #[test]
+    fn skipped_time_moves_past_the_gap() {
+        // London goes from 01:00 GMT straight to 02:00 BST
+        let start = resolve_local(local("2026-03-29 01:30"), Europe::London).unwrap();
+        assert_eq!(start, utc("2026-03-29T01:30:00Z"));
+        assert_eq!(
+            start.with_timezone(&Europe::London).naive_local(),
+            local("2026-03-29 02:30")
+        );
+    }
+
+    #[test]
+    fn repeated_time_means_the_first_one() {
+        // 01:30 happens in BST and again in GMT
+        let start = resolve_local(local("2026-10-25 01:30"), Europe::London).unwrap();
+        assert_eq!(start, utc("2026-10-25T00:30:00Z"));
+    }
+
+    #[test]
+    fn reminder_fires_once_when_clocks_go_back() {
+        let start = start_instant("2026-10-25", "02:00", Some("Europe/London")).unwrap();
+        assert!(reminder_due(start, 30, utc("2026-10-25T01:30:00Z")));
+        // The wall clock reads 01:30 again an hour later
+        assert!(!reminder_due(start, 30, utc("2026-10-25T00:30:00Z")));
+        assert!(!reminder_due(start, 30, utc("2026-10-25T02:30:00Z")));
+    }
+
+    #[test]
+    fn reminder_uses_the_offset_after_clocks_go_forward() {
+        let start = start_instant("2026-03-29", "09:00", Some("Europe/Berlin")).unwrap();
+        assert_eq!(start, utc("2026-03-29T07:00:00Z"));
+        assert!(reminder_due(start, 30, utc("2026-03-29T06:30:00Z")));
+        assert!(!reminder_due(start, 30, utc("2026-03-29T07:30:00Z")));
+    }
+
+    #[test]
+    fn duration_counts_the_repeated_hour() {
+        let slot = TimedSlot::in_zone(
+            &task("2026-10-25", "00:30", 120, "Europe/London"),
+            Europe::London,
+        )
+        .unwrap();
+        assert_eq!(slot.start, local("2026-10-25 00:30"));
+        assert_eq!(slot.end, local("2026-10-25 01:30"));
+    }
+
+    #[test]
+    fn day_follows_the_zone_it_is_viewed_from() {
+        let evening = task("2026-11-02", "23:30", 60, "America/New_York");
+
+        let at_home = TimedSlot::in_zone(&evening, America::New_York).unwrap();
+        assert_eq!(at_home.start, local("2026-11-02 23:30"));
+
+        let abroad = TimedSlot::in_zone(&evening, Europe::London).unwrap();
+        assert_eq!(abroad.start, local("2026-11-03 04:30"));
+    }
+
+    #[test]
+    fn range_days_follow_the_viewing_zone() {
+        // 22:00 in New York is 03:00 the next morning in London
+        let flight = task("2026-11-02", "22:00", 90, "America/New_York");
+        let day = |value: &str| NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap();
+
+        assert_eq!(
+            task_days(&flight, America::New_York),
+            Some((day("2026-11-02"), day("2026-11-02")))
+        );
+        assert_eq!(
+            task_days(&flight, Europe::London),
+            Some((day("2026-11-03"), day("2026-11-03")))
+        );
+    }
+
+    #[test]
+    fn offset_gap_between_zones_changes_around_dst() {
+        // The US switches a week after Europe, so the gap is briefly 4 hours
+        let call = task("2026-10-28", "09:00", 30, "America/New_York");
+        let slot = TimedSlot::in_zone(&call, Europe::London).unwrap();
+        assert_eq!(slot.start, local("2026-10-28 13:00"));
+
+        let call = task("2026-11-04", "09:00", 30, "America/New_York");
+        let slot = TimedSlot::in_zone(&call, Europe::London).unwrap();
+        assert_eq!(slot.start, local("2026-11-04 14:00"));
+    }
+}
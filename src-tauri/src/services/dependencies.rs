@@ -0,0 +1,281 @@
+use crate::database::{Database, DependencyWarning, Task, TaskDependencies, TaskRepository};
+use crate::scheduling::{zones, TimedSlot};
+use crate::services::tasks::task_days;
+use rusqlite::{params, Connection, TransactionBehavior};
+
+/// Makes `task_id` depend on `depends_on_id`, refusing anything that would
+/// close a cycle. Returns warnings if the task is scheduled too early.
+pub fn add_dependency(
+    db: &Database,
+    task_id: &str,
+    depends_on_id: &str,
+) -> Result<Vec<DependencyWarning>, String> {
+    let mut conn = db.conn()?;
+    link(&mut conn, task_id, depends_on_id)
+}
+
+fn link(
+    conn: &mut Connection,
+    task_id: &str,
+    depends_on_id: &str,
+) -> Result<Vec<DependencyWarning>, String> {
+    if task_id == depends_on_id {
+        return Err("A task can't depend on itself".to_string());
+    }
+
+    // Immediate, so no other writer can add the reverse edge between the
+    // cycle check and the insert
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| e.to_string())?;
+
+    let existing: i32 = tx
+        .query_row(
+            "SELECT COUNT(*) FROM tasks WHERE id IN (?, ?)",
+            params![task_id, depends_on_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if existing != 2 {
+        return Err("Both tasks must exist".to_string());
+    }
+
+    // Walk everything the prerequisite already depends on; finding the
+    // dependent there means the new edge would close a loop
+    let creates_cycle: bool = tx
+        .query_row(
+            "WITH RECURSIVE chain(id) AS (
+                 SELECT depends_on_id FROM task_dependencies WHERE task_id = ?1
+                 UNION
+                 SELECT d.depends_on_id FROM task_dependencies d JOIN chain c ON d.task_id = c.id
+             )
+             SELECT EXISTS(SELECT 1 FROM chain WHERE id = ?2)",
+            params![depends_on_id, task_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if creates_cycle {
+        return Err("That dependency would create a cycle".to_string());
+    }
+
+    tx.execute(
+        "INSERT OR IGNORE INTO task_dependencies (task_id, depends_on_id) VALUES (?, ?)",
+        params![task_id, depends_on_id],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    let task = TaskRepository::new(conn)
+        .get(task_id)
+        .map_err(|e| e.to_string())?;
+    warnings(conn, &task)
+}
+
+pub fn remove_dependency(db: &Database, task_id: &str, depends_on_id: &str) -> Result<(), String> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "DELETE FROM task_dependencies WHERE task_id = ? AND depends_on_id = ?",
+        params![task_id, depends_on_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+pub fn get_task_dependencies(db: &Database, task_id: &str) -> Result<TaskDependencies, String> {
+    let conn = db.conn()?;
+    related(&conn, task_id)
+}
+
+fn related(conn: &Connection, task_id: &str) -> Result<TaskDependencies, String> {
+    let tasks = TaskRepository::new(conn);
+
+    Ok(TaskDependencies {
+        prerequisites: tasks.prerequisites(task_id).map_err(|e| e.to_string())?,
+        dependents: tasks.dependents(task_id).map_err(|e| e.to_string())?,
+    })
+}
+
+/// Whether `dependent` is scheduled to start before `prerequisite` ends.
+/// Without times on both, the dependent's first day is compared with the
+/// prerequisite's last.
+fn scheduled_too_early(dependent: &Task, prerequisite: &Task) -> bool {
+    match (
+        TimedSlot::from_task(dependent),
+        TimedSlot::from_task(prerequisite),
+    ) {
+        (Some(dep), Some(pre)) => dep.start < pre.end,
+        _ => match (
+            task_days(dependent, zones::current_zone()),
+            task_days(prerequisite, zones::current_zone()),
+        ) {
+            (Some((dep_first, _)), Some((_, pre_last))) => dep_first < pre_last,
+            _ => false,
+        },
+    }
+}
+
+/// Warnings for the task against its prerequisites, and for its dependents
+/// against it, since moving either side can break the order.
+pub fn dependency_warnings(db: &Database, task: &Task) -> Result<Vec<DependencyWarning>, String> {
+    let conn = db.conn()?;
+    warnings(&conn, task)
+}
+
+fn warnings(conn: &Connection, task: &Task) -> Result<Vec<DependencyWarning>, String> {
+    let related = related(conn, &task.id)?;
+
+    let pairs = related
+        .prerequisites
+        .iter()
+        .map(|pre| (task, pre))
+        .chain(related.dependents.iter().map(|dep| (dep, task)));
+
+    Ok(pairs
+        .filter(|(dependent, prerequisite)| {
+            !dependent.is_completed && scheduled_too_early(dependent, prerequisite)
+        })
+        .map(|(dependent, prerequisite)| DependencyWarning {
+            task_id: dependent.id.clone(),
+            prerequisite_id: prerequisite.id.clone(),
+            message: format!(
+                "\"{}\" is scheduled before its prerequisite \"{}\"",
+                dependent.title, prerequisite.title
+            ),
+        })
+        .collect())
+}
+
+/// Open dependents of `prerequisite_id` that no longer wait on anything,
+/// i.e. the tasks that completing it just unblocked.
+pub fn unblocked_dependents(db: &Database, prerequisite_id: &str) -> Result<Vec<Task>, String> {
+    let conn = db.conn()?;
+    unblocked(&conn, prerequisite_id)
+}
+
+fn unblocked(conn: &Connection, prerequisite_id: &str) -> Result<Vec<Task>, String> {
+    Ok(related(conn, prerequisite_id)?
+        .dependents
+        .into_iter()
+        .filter(|task| !task.is_completed && !task.is_blocked)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::fixtures::connection;
+
+    fn task(conn: &Connection, id: &str, date: &str, time: Option<&str>) -> Task {
+        conn.execute(
+            "INSERT INTO tasks (id, title, scheduled_date, scheduled_time, time_zone,
+                                duration_minutes)
+             VALUES (?, ?, ?, ?, 'UTC', 60)",
+            params![id, id, date, time],
+        )
+        .unwrap();
+        TaskRepository::new(conn).get(id).unwrap()
+    }
+
+    fn complete(conn: &Connection, id: &str) {
+        conn.execute(
+            "UPDATE tasks SET is_completed = 1 WHERE id = ?",
+            params![id],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn cycles_are_refused() {
+        let mut conn = connection();
+        for id in ["a", "b", "c"] {
+            task(&conn, id, "2026-03-02", None);
+        }
+
+        assert!(link(&mut conn, "a", "a").is_err());
+
+        link(&mut conn, "b", "a").unwrap();
+        assert!(link(&mut conn, "a", "b").is_err());
+
+        link(&mut conn, "c", "b").unwrap();
+        assert!(link(&mut conn, "a", "c").is_err());
+
+        let prerequisites = related(&conn, "a").unwrap().prerequisites;
+        assert!(prerequisites.is_empty());
+    }
+
+    #[test]
+    fn both_tasks_must_exist() {
+        let mut conn = connection();
+        task(&conn, "a", "2026-03-02", None);
+        assert!(link(&mut conn, "a", "missing").is_err());
+    }
+
+    #[test]
+    fn warns_when_a_dependent_starts_before_its_prerequisite_ends() {
+        let mut conn = connection();
+        task(&conn, "design", "2026-03-02", Some("09:00"));
+        task(&conn, "build", "2026-03-02", Some("09:30"));
+        task(&conn, "ship", "2026-03-02", Some("10:00"));
+
+        let added = link(&mut conn, "build", "design").unwrap();
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].task_id, "build");
+        assert_eq!(added[0].prerequisite_id, "design");
+
+        // Starting right as the prerequisite ends is fine
+        assert!(link(&mut conn, "ship", "design").unwrap().is_empty());
+
+        // Checked from the prerequisite's side too
+        let design = TaskRepository::new(&conn).get("design").unwrap();
+        let flagged: Vec<_> = warnings(&conn, &design)
+            .unwrap()
+            .into_iter()
+            .map(|w| w.task_id)
+            .collect();
+        assert_eq!(flagged, ["build"]);
+
+        complete(&conn, "build");
+        assert!(warnings(&conn, &design).unwrap().is_empty());
+    }
+
+    #[test]
+    fn untimed_tasks_compare_days() {
+        let mut conn = connection();
+        task(&conn, "draft", "2026-03-03", None);
+        task(&conn, "same_day", "2026-03-03", None);
+        task(&conn, "day_before", "2026-03-02", None);
+
+        assert!(link(&mut conn, "same_day", "draft").unwrap().is_empty());
+        assert_eq!(link(&mut conn, "day_before", "draft").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn dependents_are_unblocked_once_nothing_is_left() {
+        let mut conn = connection();
+        for id in ["a", "b", "c", "d"] {
+            task(&conn, id, "2026-03-02", None);
+        }
+        // c waits on a and b; d waits on a only
+        link(&mut conn, "c", "a").unwrap();
+        link(&mut conn, "c", "b").unwrap();
+        link(&mut conn, "d", "a").unwrap();
+
+        complete(&conn, "a");
+        let ids: Vec<_> = unblocked(&conn, "a")
+            .unwrap()
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(ids, ["d"]);
+
+        complete(&conn, "b");
+        let ids: Vec<_> = unblocked(&conn, "b")
+            .unwrap()
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(ids, ["c"]);
+    }
+}
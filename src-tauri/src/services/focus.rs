@@ -0,0 +1,412 @@
+use crate::database::{
+    Database, FocusDayStats, FocusPhaseChange, FocusSession, FocusTaskStats, StartFocusInput,
+};
+use crate::scheduling::zones;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+const FOCUS_SESSION_COLUMNS: &str = "id, task_id, work_minutes, short_break_minutes,
+     long_break_minutes, long_break_every, phase, phase_started_at, completed_cycles, started_at";
+
+fn focus_session_from_row(row: &Row) -> rusqlite::Result<FocusSession> {
+    let mut session = FocusSession {
+        id: row.get(0)?,
+        task_id: row.get(1)?,
+        work_minutes: row.get(2)?,
+        short_break_minutes: row.get(3)?,
+        long_break_minutes: row.get(4)?,
+        long_break_every: row.get(5)?,
+        phase: row.get(6)?,
+        phase_started_at: row.get(7)?,
+        phase_ends_at: String::new(),
+        completed_cycles: row.get(8)?,
+        started_at: row.get(9)?,
+    };
+    session.phase_ends_at = phase_end(&session)
+        .map(format_timestamp)
+        .unwrap_or_default();
+    Ok(session)
+}
+
+fn format_timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+fn phase_minutes(session: &FocusSession) -> i32 {
+    match session.phase.as_str() {
+        "short_break" => session.short_break_minutes,
+        "long_break" => session.long_break_minutes,
+        _ => session.work_minutes,
+    }
+}
+
+fn phase_end(session: &FocusSession) -> Option<DateTime<Utc>> {
+    parse_timestamp(&session.phase_started_at)
+        .map(|start| start + Duration::minutes(phase_minutes(session) as i64))
+}
+
+fn running_session(conn: &Connection) -> rusqlite::Result<Option<FocusSession>> {
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM focus_sessions WHERE ended_at IS NULL",
+            FOCUS_SESSION_COLUMNS
+        ),
+        [],
+        focus_session_from_row,
+    )
+    .optional()
+}
+
+/// Starts a focus session on the task, ending any session already running.
+pub fn start_focus_session(db: &Database, input: StartFocusInput) -> Result<FocusSession, String> {
+    let work_minutes = input.work_minutes.unwrap_or(25);
+    let short_break_minutes = input.short_break_minutes.unwrap_or(5);
+    let long_break_minutes = input.long_break_minutes.unwrap_or(15);
+    let long_break_every = input.long_break_every.unwrap_or(4);
+
+    if work_minutes <= 0 || short_break_minutes <= 0 || long_break_minutes <= 0 {
+        return Err("Focus and break lengths must be positive".to_string());
+    }
+    if long_break_every <= 0 {
+        return Err("Long break interval must be at least one cycle".to_string());
+    }
+
+    let mut conn = db.conn()?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    let exists: bool = tx
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM tasks WHERE id = ?)",
+            params![input.task_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if !exists {
+        return Err(format!("No task with id {}", input.task_id));
+    }
+
+    let now = format_timestamp(Utc::now());
+    tx.execute(
+        "UPDATE focus_sessions SET ended_at = ? WHERE ended_at IS NULL",
+        params![now],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let id = Uuid::new_v4().to_string();
+    tx.execute(
+        "INSERT INTO focus_sessions (id, task_id, work_minutes, short_break_minutes,
+                                     long_break_minutes, long_break_every, phase,
+                                     phase_started_at, started_at)
+         VALUES (?, ?, ?, ?, ?, ?, 'work', ?, ?)",
+        params![
+            id,
+            input.task_id,
+            work_minutes,
+            short_break_minutes,
+            long_break_minutes,
+            long_break_every,
+            now,
+            now,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let session = running_session(&tx)
+        .map_err(|e| e.to_string())?
+        .ok_or("Focus session was not saved")?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(session)
+}
+
+/// Ends the running session. A work phase cut short is not recorded.
+pub fn stop_focus_session(db: &Database) -> Result<Option<FocusSession>, String> {
+    let conn = db.conn()?;
+
+    let session = running_session(&conn).map_err(|e| e.to_string())?;
+    if let Some(ref session) = session {
+        conn.execute(
+            "UPDATE focus_sessions SET ended_at = ? WHERE id = ?",
+            params![format_timestamp(Utc::now()), session.id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(session)
+}
+
+/// The running session, so a reloaded window can resume its countdown.
+pub fn get_focus_session(db: &Database) -> Result<Option<FocusSession>, String> {
+    let conn = db.conn()?;
+    running_session(&conn).map_err(|e| e.to_string())
+}
+
+/// Moves the running session into its next phase once the current one is
+/// over, recording finished work phases. Called by the scheduler every tick.
+///
+/// The next phase starts at `now` rather than when the last one was due, so
+/// a session left running while the app was closed resumes instead of
+/// replaying every missed cycle.
+pub fn advance_focus_session(
+    db: &Database,
+    now: DateTime<Utc>,
+) -> Result<Option<FocusPhaseChange>, String> {
+    let conn = db.conn()?;
+    advance(&conn, now)
+}
+
+fn advance(conn: &Connection, now: DateTime<Utc>) -> Result<Option<FocusPhaseChange>, String> {
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+
+    let Some(session) = running_session(&tx).map_err(|e| e.to_string())? else {
+        return Ok(None);
+    };
+    let Some(ends_at) = phase_end(&session) else {
+        return Err(format!(
+            "Focus session {} has an invalid phase start",
+            session.id
+        ));
+    };
+    if now < ends_at {
+        return Ok(None);
+    }
+
+    let mut completed_cycles = session.completed_cycles;
+    let next_phase = if session.phase == "work" {
+        let started_at = parse_timestamp(&session.phase_started_at).unwrap_or(ends_at);
+        tx.execute(
+            "INSERT INTO focus_cycles (id, session_id, task_id, cycle_date, started_at,
+                                       ended_at, minutes)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+            params![
+                Uuid::new_v4().to_string(),
+                session.id,
+                session.task_id,
+                started_at
+                    .with_timezone(&zones::current_zone())
+                    .format("%Y-%m-%d")
+                    .to_string(),
+                session.phase_started_at,
+                format_timestamp(ends_at),
+                session.work_minutes,
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+
+        completed_cycles += 1;
+        if completed_cycles % session.long_break_every == 0 {
+            "long_break"
+        } else {
+            "short_break"
+        }
+    } else {
+        "work"
+    };
+
+    tx.execute(
+        "UPDATE focus_sessions SET phase = ?, phase_started_at = ?, completed_cycles = ?
+         WHERE id = ?",
+        params![
+            next_phase,
+            format_timestamp(now),
+            completed_cycles,
+            session.id
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let updated = running_session(&tx)
+        .map_err(|e| e.to_string())?
+        .ok_or("Focus session disappeared")?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(Some(FocusPhaseChange {
+        previous_phase: session.phase,
+        session: updated,
+    }))
+}
+
+/// Completed work cycles per day between the two dates, broken down by task.
+pub fn get_focus_stats(
+    db: &Database,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<FocusDayStats>, String> {
+    let conn = db.conn()?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT c.cycle_date, c.task_id, t.title, COUNT(*), SUM(c.minutes)
+             FROM focus_cycles c
+             JOIN tasks t ON t.id = c.task_id
+             WHERE c.cycle_date >= ? AND c.cycle_date <= ?
+             GROUP BY c.cycle_date, c.task_id
+             ORDER BY c.cycle_date, t.title",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(params![start_date, end_date], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                FocusTaskStats {
+                    task_id: row.get(1)?,
+                    title: row.get(2)?,
+                    cycles: row.get(3)?,
+                    focus_minutes: row.get(4)?,
+                },
+            ))
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut days: BTreeMap<String, FocusDayStats> = BTreeMap::new();
+    for (date, task) in rows {
+        let day = days.entry(date.clone()).or_insert_with(|| FocusDayStats {
+            date,
+            cycles: 0,
+            focus_minutes: 0,
+            tasks: Vec::new(),
+        });
+        day.cycles += task.cycles;
+        day.focus_minutes += task.focus_minutes;
+        day.tasks.push(task);
+    }
+
+    Ok(days.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::fixtures;
+
+    fn connection() -> Connection {
+        let conn = fixtures::connection();
+        conn.execute(
+            "INSERT INTO tasks (id, title, scheduled_date) VALUES ('t', 'Task', '2026-03-02')",
+            [],
+        )
+        .unwrap();
+        conn
+    }
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        parse_timestamp(value).unwrap()
+    }
+
+    /// A running session with 25/5/15 minute phases and a long break every
+    /// second cycle, which started its work phase at `started_at`.
+    fn session(conn: &Connection, started_at: &str) {
+        conn.execute(
+            "INSERT INTO focus_sessions (id, task_id, work_minutes, short_break_minutes,
+                                         long_break_minutes, long_break_every, phase,
+                                         phase_started_at, started_at)
+             VALUES ('s', 't', 25, 5, 15, 2, 'work', ?1, ?1)",
+            params![started_at],
+        )
+        .unwrap();
+    }
+
+    fn cycles(conn: &Connection) -> Vec<(String, String, i32)> {
+        let mut stmt = conn
+            .prepare("SELECT started_at, ended_at, minutes FROM focus_cycles ORDER BY started_at")
+            .unwrap();
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn phase_is_kept_until_it_is_over() {
+        let conn = connection();
+        session(&conn, "2026-03-02T09:00:00Z");
+
+        assert!(advance(&conn, utc("2026-03-02T09:24:59Z"))
+            .unwrap()
+            .is_none());
+        assert!(cycles(&conn).is_empty());
+    }
+
+    #[test]
+    fn breaks_alternate_with_a_long_break_every_n_cycles() {
+        let conn = connection();
+        session(&conn, "2026-03-02T09:00:00Z");
+
+        let mut phases = Vec::new();
+        for now in [
+            "2026-03-02T09:25:00Z",
+            "2026-03-02T09:30:00Z",
+            "2026-03-02T09:55:00Z",
+            "2026-03-02T10:10:00Z",
+            "2026-03-02T10:35:00Z",
+        ] {
+            let change = advance(&conn, utc(now)).unwrap().unwrap();
+            assert_eq!(change.session.phase_started_at, now);
+            phases.push((change.previous_phase, change.session.phase));
+        }
+
+        let phases: Vec<_> = phases
+            .iter()
+            .map(|(from, to)| (from.as_str(), to.as_str()))
+            .collect();
+        assert_eq!(
+            phases,
+            [
+                ("work", "short_break"),
+                ("short_break", "work"),
+                ("work", "long_break"),
+                ("long_break", "work"),
+                ("work", "short_break"),
+            ]
+        );
+    }
+
+    #[test]
+    fn finished_work_phases_are_recorded_as_cycles() {
+        let conn = connection();
+        session(&conn, "2026-03-02T09:00:00Z");
+
+        // Noticed late: the cycle still ends when it was due
+        let change = advance(&conn, utc("2026-03-02T09:40:00Z"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(change.session.completed_cycles, 1);
+        assert_eq!(change.session.phase_started_at, "2026-03-02T09:40:00Z");
+
+        advance(&conn, utc("2026-03-02T09:45:00Z")).unwrap();
+        advance(&conn, utc("2026-03-02T10:10:00Z")).unwrap();
+        assert_eq!(
+            cycles(&conn),
+            [
+                (
+                    "2026-03-02T09:00:00Z".to_string(),
+                    "2026-03-02T09:25:00Z".to_string(),
+                    25
+                ),
+                (
+                    "2026-03-02T09:45:00Z".to_string(),
+                    "2026-03-02T10:10:00Z".to_string(),
+                    25
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn nothing_happens_without_a_running_session() {
+        let conn = connection();
+        assert!(advance(&conn, Utc::now()).unwrap().is_none());
+    }
+}
@@ -0,0 +1,488 @@
+use crate::database::{
+    DailyGoal, Database, GoalPeriodStats, GoalRepository, GoalStats, GoalTotals, GoalWeekdayStats,
+    InsertGoalInput, PeriodGoal, PeriodGoalRepository, PeriodKey, SettingsRepository, Task,
+    TaskRepository, UpsertGoalInput, UpsertPeriodGoalInput,
+};
+use chrono::{Duration, NaiveDate};
+use rusqlite::{Connection, TransactionBehavior};
+use std::collections::HashSet;
+
+/// Whether unfinished goals move to the next day on their own.
+pub const CARRY_OVER_SETTING: &str = "goals.carry_over";
+/// The last day automatic carry-over ran, so it runs once a day.
+const CARRY_OVER_LAST_RUN: &str = "goals.carry_over_last_run";
+
+pub fn get_goals_for_date(db: &Database, date: &str) -> Result<Vec<DailyGoal>, String> {
+    let conn = db.conn()?;
+
+    GoalRepository::new(&conn)
+        .for_date(date)
+        .map_err(|e| e.to_string())
+}
+
+pub fn upsert_goal(db: &Database, input: UpsertGoalInput) -> Result<DailyGoal, String> {
+    // Immediate, so two saves to an empty slot can't both insert
+    let mut conn = db.conn()?;
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| e.to_string())?;
+    let goals = GoalRepository::new(&tx);
+
+    // Check if goal exists for this date and order
+    let existing = goals
+        .id_at(&input.goal_date.to_string(), input.goal_order)
+        .map_err(|e| e.to_string())?;
+
+    if let Some(ref parent_goal_id) = input.parent_goal_id {
+        check_parent(&tx, &input.goal_date.to_string(), parent_goal_id)?;
+    }
+
+    let id = match existing {
+        Some(id) => goals.set_text(&id, &input.goal_text).map(|_| id),
+        None => goals.insert(&input),
+    }
+    .map_err(|e| e.to_string())?;
+
+    if let Some(ref parent_goal_id) = input.parent_goal_id {
+        goals
+            .set_parent(&id, Some(parent_goal_id))
+            .map_err(|e| e.to_string())?;
+    }
+
+    let goal = goals.get(&id).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(goal)
+}
+
+/// Adds a goal among the day's others, renumbering them from 1 so there
+/// is room at its position.
+pub fn insert_goal(db: &Database, input: InsertGoalInput) -> Result<DailyGoal, String> {
+    let date = input.goal_date.to_string();
+
+    let mut conn = db.conn()?;
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| e.to_string())?;
+    let goals = GoalRepository::new(&tx);
+
+    if let Some(ref parent_goal_id) = input.parent_goal_id {
+        check_parent(&tx, &date, parent_goal_id)?;
+    }
+
+    let current = goals.for_date(&date).map_err(|e| e.to_string())?;
+    let index = match input.position {
+        Some(position) => (position.max(1) as usize - 1).min(current.len()),
+        None => current.len(),
+    };
+
+    let orders: Vec<(&str, i32)> = current
+        .iter()
+        .enumerate()
+        .map(|(i, goal)| {
+            let slot = if i < index { i + 1 } else { i + 2 };
+            (goal.id.as_str(), slot as i32)
+        })
+        .collect();
+    goals
+        .set_orders(&date, &orders)
+        .map_err(|e| e.to_string())?;
+
+    let id = goals
+        .insert(&UpsertGoalInput {
+            goal_date: input.goal_date,
+            goal_text: input.goal_text,
+            goal_order: index as i32 + 1,
+            parent_goal_id: input.parent_goal_id,
+        })
+        .map_err(|e| e.to_string())?;
+
+    let goal = goals.get(&id).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(goal)
+}
+
+/// Puts the day's goals in the given order, numbered from 1.
+/// `ordered_ids` has to list each of the day's goals exactly once.
+pub fn reorder_goals(
+    db: &Database,
+    date: &str,
+    ordered_ids: &[String],
+) -> Result<Vec<DailyGoal>, String> {
+    let mut conn = db.conn()?;
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| e.to_string())?;
+    let goals = GoalRepository::new(&tx);
+
+    let mut current: Vec<String> = goals
+        .for_date(date)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|goal| goal.id)
+        .collect();
+    let mut given = ordered_ids.to_vec();
+    current.sort();
+    given.sort();
+    if current != given {
+        return Err(format!(
+            "The new order has to list each goal for {} once",
+            date
+        ));
+    }
+
+    let orders: Vec<(&str, i32)> = ordered_ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (id.as_str(), i as i32 + 1))
+        .collect();
+    goals.set_orders(date, &orders).map_err(|e| e.to_string())?;
+
+    let reordered = goals.for_date(date).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(reordered)
+}
+
+/// A day's goal can only work towards a period goal covering that day.
+fn check_parent(conn: &Connection, goal_date: &str, parent_goal_id: &str) -> Result<(), String> {
+    let parent = PeriodGoalRepository::new(conn)
+        .get(parent_goal_id)
+        .map_err(|_| format!("No period goal with id {}", parent_goal_id))?;
+
+    if goal_date < parent.start_date.as_str() || goal_date > parent.end_date.as_str() {
+        return Err(format!(
+            "A goal on {} can't count towards a goal for {}",
+            goal_date, parent.period_key
+        ));
+    }
+    Ok(())
+}
+
+/// Links a day's goal to a period goal, or unlinks it when `parent_goal_id`
+/// is `None`.
+pub fn set_goal_parent(
+    db: &Database,
+    id: &str,
+    parent_goal_id: Option<&str>,
+) -> Result<DailyGoal, String> {
+    let conn = db.conn()?;
+    let goals = GoalRepository::new(&conn);
+    let goal = goals.get(id).map_err(|e| e.to_string())?;
+
+    if let Some(parent_goal_id) = parent_goal_id {
+        check_parent(&conn, &goal.goal_date, parent_goal_id)?;
+    }
+
+    goals
+        .set_parent(id, parent_goal_id)
+        .map_err(|e| e.to_string())?;
+    goals.get(id).map_err(|e| e.to_string())
+}
+
+pub fn toggle_goal_completion(db: &Database, id: &str) -> Result<DailyGoal, String> {
+    let conn = db.conn()?;
+
+    GoalRepository::new(&conn)
+        .toggle_completion(id)
+        .map_err(|e| e.to_string())
+}
+
+/// Deletes the goal, returning it so callers know which parent goal changed.
+pub fn delete_goal(db: &Database, id: &str) -> Result<DailyGoal, String> {
+    let conn = db.conn()?;
+    let goals = GoalRepository::new(&conn);
+
+    let goal = goals.get(id).map_err(|e| e.to_string())?;
+    goals.delete(id).map_err(|e| e.to_string())?;
+    Ok(goal)
+}
+
+/// Links a task to a goal, so the goal shows its progress and, with
+/// auto-complete on, completes along with it.
+pub fn link_task_to_goal(db: &Database, id: &str, task_id: &str) -> Result<DailyGoal, String> {
+    let conn = db.conn()?;
+    let goals = GoalRepository::new(&conn);
+    goals.get(id).map_err(|e| e.to_string())?;
+    TaskRepository::new(&conn)
+        .get(task_id)
+        .map_err(|e| e.to_string())?;
+
+    goals.link_task(id, task_id).map_err(|e| e.to_string())?;
+    goals.sync_with_tasks(id).map_err(|e| e.to_string())?;
+    goals.get(id).map_err(|e| e.to_string())
+}
+
+pub fn unlink_task_from_goal(db: &Database, id: &str, task_id: &str) -> Result<DailyGoal, String> {
+    let conn = db.conn()?;
+    let goals = GoalRepository::new(&conn);
+
+    goals.unlink_task(id, task_id).map_err(|e| e.to_string())?;
+    goals.sync_with_tasks(id).map_err(|e| e.to_string())?;
+    goals.get(id).map_err(|e| e.to_string())
+}
+
+pub fn get_goal_tasks(db: &Database, id: &str) -> Result<Vec<Task>, String> {
+    let conn = db.conn()?;
+
+    TaskRepository::new(&conn)
+        .linked_to_goal(id)
+        .map_err(|e| e.to_string())
+}
+
+/// Turning auto-complete on brings the goal in line with its linked tasks
+/// straight away.
+pub fn set_goal_auto_complete(
+    db: &Database,
+    id: &str,
+    auto_complete: bool,
+) -> Result<DailyGoal, String> {
+    let conn = db.conn()?;
+    let goals = GoalRepository::new(&conn);
+
+    goals
+        .set_auto_complete(id, auto_complete)
+        .map_err(|e| e.to_string())?;
+    goals.sync_with_tasks(id).map_err(|e| e.to_string())?;
+    goals.get(id).map_err(|e| e.to_string())
+}
+
+/// Copies the unfinished goals of `from_date` into the free slots of
+/// `to_date`, lowest slot first. Goals already carried there are skipped,
+/// and a link to a period goal is kept only if the period covers `to_date`.
+pub fn carry_over_goals(
+    db: &Database,
+    from_date: &str,
+    to_date: &str,
+) -> Result<Vec<DailyGoal>, String> {
+    if to_date <= from_date {
+        return Err("Goals can only be carried forward".to_string());
+    }
+
+    // Taking the write lock up front keeps another connection from filling
+    // the free slots between reading them and inserting
+    let mut conn = db.conn()?;
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| e.to_string())?;
+    let carried = carry_over(&tx, from_date, to_date).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(carried)
+}
+
+fn carry_over(
+    conn: &Connection,
+    from_date: &str,
+    to_date: &str,
+) -> rusqlite::Result<Vec<DailyGoal>> {
+    let goals = GoalRepository::new(conn);
+    let periods = PeriodGoalRepository::new(conn);
+
+    let mut taken: HashSet<i32> = goals
+        .for_date(to_date)?
+        .iter()
+        .map(|goal| goal.goal_order)
+        .collect();
+    let mut next_slot = 1;
+
+    let mut carried = Vec::new();
+    for goal in goals.for_date(from_date)? {
+        if goal.is_completed || goals.has_lineage_on(&goal, to_date)? {
+            continue;
+        }
+
+        while taken.contains(&next_slot) {
+            next_slot += 1;
+        }
+        taken.insert(next_slot);
+
+        let parent = match goal.parent_goal_id.as_deref() {
+            Some(id) => periods
+                .get(id)
+                .ok()
+                .filter(|p| p.start_date.as_str() <= to_date && to_date <= p.end_date.as_str())
+                .map(|p| p.id),
+            None => None,
+        };
+
+        let id = goals.insert_carried(&goal, to_date, next_slot, parent.as_deref())?;
+        carried.push(goals.get(&id)?);
+    }
+
+    Ok(carried)
+}
+
+pub fn get_goal_carry_over(db: &Database) -> Result<bool, String> {
+    let conn = db.conn()?;
+
+    SettingsRepository::new(&conn)
+        .flag(CARRY_OVER_SETTING)
+        .map_err(|e| e.to_string())
+}
+
+pub fn set_goal_carry_over(db: &Database, enabled: bool) -> Result<(), String> {
+    let conn = db.conn()?;
+
+    SettingsRepository::new(&conn)
+        .set_flag(CARRY_OVER_SETTING, enabled)
+        .map_err(|e| e.to_string())
+}
+
+/// With automatic carry-over on, moves yesterday's unfinished goals onto
+/// `today`. Runs once a day, so a carried goal deleted later stays deleted.
+pub fn auto_carry_over_goals(db: &Database, today: NaiveDate) -> Result<Vec<DailyGoal>, String> {
+    let today_key = today.format("%Y-%m-%d").to_string();
+    let yesterday = (today - Duration::days(1)).format("%Y-%m-%d").to_string();
+
+    let mut conn = db.conn()?;
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| e.to_string())?;
+    let settings = SettingsRepository::new(&tx);
+
+    let enabled = settings
+        .flag(CARRY_OVER_SETTING)
+        .map_err(|e| e.to_string())?;
+    let last_run = settings
+        .get(CARRY_OVER_LAST_RUN)
+        .map_err(|e| e.to_string())?;
+    if !enabled || last_run.as_deref() >= Some(today_key.as_str()) {
+        return Ok(Vec::new());
+    }
+
+    let carried = carry_over(&tx, &yesterday, &today_key).map_err(|e| e.to_string())?;
+    settings
+        .set(CARRY_OVER_LAST_RUN, &today_key)
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(carried)
+}
+
+pub fn get_goals_for_period(db: &Database, key: &PeriodKey) -> Result<Vec<PeriodGoal>, String> {
+    let conn = db.conn()?;
+
+    PeriodGoalRepository::new(&conn)
+        .for_period(key)
+        .map_err(|e| e.to_string())
+}
+
+pub fn get_period_goal(db: &Database, id: &str) -> Result<PeriodGoal, String> {
+    let conn = db.conn()?;
+
+    PeriodGoalRepository::new(&conn)
+        .get(id)
+        .map_err(|e| e.to_string())
+}
+
+pub fn upsert_period_goal(
+    db: &Database,
+    input: UpsertPeriodGoalInput,
+) -> Result<PeriodGoal, String> {
+    let conn = db.conn()?;
+    let goals = PeriodGoalRepository::new(&conn);
+
+    let existing = goals
+        .id_at(&input.period_key, input.goal_order)
+        .map_err(|e| e.to_string())?;
+
+    let id = match existing {
+        Some(id) => goals.set_text(&id, &input.goal_text).map(|_| id),
+        None => goals.insert(&input),
+    }
+    .map_err(|e| e.to_string())?;
+
+    goals.get(&id).map_err(|e| e.to_string())
+}
+
+pub fn toggle_period_goal_completion(db: &Database, id: &str) -> Result<PeriodGoal, String> {
+    let conn = db.conn()?;
+
+    PeriodGoalRepository::new(&conn)
+        .toggle_completion(id)
+        .map_err(|e| e.to_string())
+}
+
+pub fn delete_period_goal(db: &Database, id: &str) -> Result<(), String> {
+    let conn = db.conn()?;
+
+    PeriodGoalRepository::new(&conn)
+        .delete(id)
+        .map_err(|e| e.to_string())
+}
+
+/// Monday first, indexed by SQLite's weekday number (Sunday is 0).
+pub(crate) const WEEKDAYS: [(&str, &str); 7] = [
+    ("1", "Monday"),
+    ("2", "Tuesday"),
+    ("3", "Wednesday"),
+    ("4", "Thursday"),
+    ("5", "Friday"),
+    ("6", "Saturday"),
+    ("0", "Sunday"),
+];
+
+/// Completion rates, streaks and weekday breakdown for the goals between
+/// the two dates. The current streak is the one still running on
+/// `end_date` and may have started before `start_date`.
+pub fn get_goal_stats(
+    db: &Database,
+    start_date: &str,
+    end_date: &str,
+) -> Result<GoalStats, String> {
+    if end_date < start_date {
+        return Err("End date can't be before the start date".to_string());
+    }
+
+    let conn = db.conn()?;
+    let goals = GoalRepository::new(&conn);
+
+    let periods = |rows: Vec<(String, GoalTotals)>| {
+        rows.into_iter()
+            .map(|(period_start, totals)| GoalPeriodStats {
+                period_start,
+                totals,
+            })
+            .collect()
+    };
+
+    let mut by_weekday = goals
+        .weekday_totals(start_date, end_date)
+        .map_err(|e| e.to_string())?;
+    let weekdays = WEEKDAYS
+        .iter()
+        .map(|(number, name)| GoalWeekdayStats {
+            weekday: name.to_string(),
+            totals: by_weekday
+                .iter()
+                .position(|(key, _)| key == number)
+                .map(|i| by_weekday.swap_remove(i).1)
+                .unwrap_or_default(),
+        })
+        .collect();
+
+    Ok(GoalStats {
+        start_date: start_date.to_string(),
+        end_date: end_date.to_string(),
+        totals: goals
+            .totals(start_date, end_date)
+            .map_err(|e| e.to_string())?,
+        current_streak: goals
+            .streak_through(end_date)
+            .map_err(|e| e.to_string())?
+            .unwrap_or_default(),
+        longest_streak: goals
+            .longest_streak(start_date, end_date)
+            .map_err(|e| e.to_string())?
+            .unwrap_or_default(),
+        weeks: periods(
+            goals
+                .weekly_totals(start_date, end_date)
+                .map_err(|e| e.to_string())?,
+        ),
+        months: periods(
+            goals
+                .monthly_totals(start_date, end_date)
+                .map_err(|e| e.to_string())?,
+        ),
+        weekdays,
+    })
+}
@@ -0,0 +1,274 @@
+use crate::database::{
+    Database, Habit, HabitCheckIn, HabitDay, HabitInput, HabitRepository, HabitSchedule,
+    HabitStats, HabitStatus,
+};
+use crate::scheduling::zones;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc};
+use std::collections::HashMap;
+
+fn validate(input: &HabitInput) -> Result<(), String> {
+    if input.name.trim().is_empty() {
+        return Err("Habit name can't be empty".to_string());
+    }
+    match input.schedule {
+        HabitSchedule::Weekdays { ref days } if days.is_empty() => {
+            return Err("Pick at least one day for the habit".to_string());
+        }
+        HabitSchedule::PerWeek { times } if !(1..=7).contains(&times) => {
+            return Err("A habit can be done 1 to 7 times a week".to_string());
+        }
+        _ => {}
+    }
+    if input.target_value.is_some_and(|target| target <= 0.0) {
+        return Err("Habit target must be positive".to_string());
+    }
+    Ok(())
+}
+
+pub fn get_habits(db: &Database, include_archived: bool) -> Result<Vec<Habit>, String> {
+    let conn = db.conn()?;
+
+    HabitRepository::new(&conn)
+        .all(include_archived)
+        .map_err(|e| e.to_string())
+}
+
+/// Every active habit with whether it is due on `date` and the day's
+/// check-in.
+pub fn get_habits_for_date(db: &Database, date: NaiveDate) -> Result<Vec<HabitStatus>, String> {
+    let conn = db.conn()?;
+    let habits = HabitRepository::new(&conn);
+    let key = date.format("%Y-%m-%d").to_string();
+
+    habits
+        .all(false)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|habit| {
+            Ok(HabitStatus {
+                is_due: habit.schedule.is_due(date),
+                check_in: habits
+                    .check_in_on(&habit.id, &key)
+                    .map_err(|e| e.to_string())?,
+                week_completed: habits
+                    .completed_in_week(&habit.id, date)
+                    .map_err(|e| e.to_string())?,
+                habit,
+            })
+        })
+        .collect()
+}
+
+pub fn create_habit(db: &Database, input: HabitInput) -> Result<Habit, String> {
+    validate(&input)?;
+    let conn = db.conn()?;
+    let habits = HabitRepository::new(&conn);
+
+    let id = habits.insert(&input).map_err(|e| e.to_string())?;
+    habits.get(&id).map_err(|e| e.to_string())
+}
+
+pub fn update_habit(db: &Database, id: &str, input: HabitInput) -> Result<Habit, String> {
+    validate(&input)?;
+    let conn = db.conn()?;
+    let habits = HabitRepository::new(&conn);
+
+    habits.get(id).map_err(|e| e.to_string())?;
+    habits.update(id, &input).map_err(|e| e.to_string())?;
+    habits.get(id).map_err(|e| e.to_string())
+}
+
+/// Archived habits keep their history but drop out of the day's list and
+/// stop reminding.
+pub fn set_habit_archived(db: &Database, id: &str, archived: bool) -> Result<Habit, String> {
+    let conn = db.conn()?;
+    let habits = HabitRepository::new(&conn);
+
+    habits
+        .set_archived(id, archived)
+        .map_err(|e| e.to_string())?;
+    habits.get(id).map_err(|e| e.to_string())
+}
+
+pub fn delete_habit(db: &Database, id: &str) -> Result<(), String> {
+    let conn = db.conn()?;
+
+    HabitRepository::new(&conn)
+        .delete(id)
+        .map_err(|e| e.to_string())
+}
+
+/// Records the habit as done on `date`, with how much for a habit that
+/// has a target.
+pub fn check_in_habit(
+    db: &Database,
+    id: &str,
+    date: &str,
+    value: Option<f64>,
+) -> Result<HabitCheckIn, String> {
+    if value.is_some_and(|value| value < 0.0) {
+        return Err("Check-in value can't be negative".to_string());
+    }
+
+    let conn = db.conn()?;
+    let habits = HabitRepository::new(&conn);
+    let habit = habits.get(id).map_err(|e| e.to_string())?;
+    if habit.target_value.is_some() && value.is_none() {
+        return Err(format!("Check-ins for \"{}\" need a value", habit.name));
+    }
+
+    habits
+        .check_in(id, date, value)
+        .map_err(|e| e.to_string())?;
+    habits
+        .check_in_on(id, date)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Check-in was not saved".to_string())
+}
+
+pub fn remove_habit_check_in(db: &Database, id: &str, date: &str) -> Result<(), String> {
+    let conn = db.conn()?;
+
+    HabitRepository::new(&conn)
+        .remove_check_in(id, date)
+        .map_err(|e| e.to_string())
+}
+
+/// Streaks, completion rate and one heatmap cell per day between the two
+/// dates. The current streak is the one still running on `end_date`.
+pub fn get_habit_stats(
+    db: &Database,
+    id: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<HabitStats, String> {
+    if end_date < start_date {
+        return Err("End date can't be before the start date".to_string());
+    }
+
+    let conn = db.conn()?;
+    let habits = HabitRepository::new(&conn);
+    let habit = habits.get(id).map_err(|e| e.to_string())?;
+
+    let start = start_date.format("%Y-%m-%d").to_string();
+    let end = end_date.format("%Y-%m-%d").to_string();
+    let check_ins: HashMap<String, HabitCheckIn> = habits
+        .check_ins(id, &start, &end)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|check_in| (check_in.checkin_date.clone(), check_in))
+        .collect();
+
+    let days: Vec<HabitDay> = start_date
+        .iter_days()
+        .take_while(|day| *day <= end_date)
+        .map(|day| {
+            let date = day.format("%Y-%m-%d").to_string();
+            let check_in = check_ins.get(&date);
+            let level = match (check_in, habit.target_value) {
+                (None, _) => 0.0,
+                (Some(_), None) => 1.0,
+                (Some(check_in), Some(target)) => (check_in.value.unwrap_or(0.0) / target).min(1.0),
+            };
+            HabitDay {
+                is_due: habit.schedule.is_due(day),
+                value: check_in.and_then(|check_in| check_in.value),
+                is_complete: check_in.is_some_and(|check_in| check_in.is_complete),
+                level,
+                date,
+            }
+        })
+        .collect();
+
+    let (due_days, completed_days) = match habit.schedule {
+        // Each week asks for `times` days, and extra days don't make up
+        // for a short week
+        HabitSchedule::PerWeek { times } => {
+            let mut weeks: HashMap<NaiveDate, i32> = HashMap::new();
+            for (day, cell) in start_date.iter_days().zip(&days) {
+                let monday = day - Duration::days(day.weekday().num_days_from_monday() as i64);
+                *weeks.entry(monday).or_default() += cell.is_complete as i32;
+            }
+            (
+                weeks.len() as i32 * times,
+                weeks.values().map(|done| (*done).min(times)).sum(),
+            )
+        }
+        _ => (
+            days.iter().filter(|day| day.is_due).count() as i32,
+            days.iter()
+                .filter(|day| day.is_due && day.is_complete)
+                .count() as i32,
+        ),
+    };
+
+    Ok(HabitStats {
+        habit_id: habit.id.clone(),
+        start_date: start,
+        end_date: end,
+        streak_unit: match habit.schedule {
+            HabitSchedule::PerWeek { .. } => "weeks",
+            _ => "days",
+        }
+        .to_string(),
+        current_streak: habits
+            .streak_through(&habit, end_date)
+            .map_err(|e| e.to_string())?,
+        longest_streak: habits
+            .longest_streak(&habit, start_date, end_date)
+            .map_err(|e| e.to_string())?,
+        due_days,
+        completed_days,
+        completion_rate: if due_days > 0 {
+            completed_days as f64 / due_days as f64
+        } else {
+            0.0
+        },
+        days,
+    })
+}
+
+/// Habits whose reminder is due at `now`: due today in the app's zone, not
+/// yet done, and not reminded today. Each is marked as reminded.
+pub fn due_habit_reminders(db: &Database, now: DateTime<Utc>) -> Result<Vec<Habit>, String> {
+    let conn = db.conn()?;
+    let habits = HabitRepository::new(&conn);
+    let zone = zones::current_zone();
+    let today = now.with_timezone(&zone).date_naive();
+    let key = today.format("%Y-%m-%d").to_string();
+
+    let mut due = Vec::new();
+    for habit in habits.unreminded(&key).map_err(|e| e.to_string())? {
+        let Some(fire_at) = habit
+            .reminder_time
+            .as_deref()
+            .and_then(|time| NaiveTime::parse_from_str(time, "%H:%M").ok())
+            .and_then(|time| zones::resolve_local(today.and_time(time), zone))
+        else {
+            continue;
+        };
+        if !habit.schedule.is_due(today) || !zones::reminder_due(fire_at, 0, now) {
+            continue;
+        }
+
+        let done = match habit.schedule {
+            HabitSchedule::PerWeek { times } => {
+                habits
+                    .completed_in_week(&habit.id, today)
+                    .map_err(|e| e.to_string())?
+                    >= times
+            }
+            _ => habits
+                .check_in_on(&habit.id, &key)
+                .map_err(|e| e.to_string())?
+                .is_some_and(|check_in| check_in.is_complete),
+        };
+        habits
+            .set_reminded(&habit.id, &key)
+            .map_err(|e| e.to_string())?;
+        if !done {
+            due.push(habit);
+        }
+    }
+    Ok(due)
+}
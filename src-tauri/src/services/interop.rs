@@ -0,0 +1,131 @@
+use crate::database::{
+    CreateTaskInput, DailyGoal, Database, Date, GoalRepository, JournalEntry, JournalRepository,
+    Task, TaskRepository,
+};
+use crate::interop::todo_txt::TodoItem;
+use crate::interop::{self, day_one, markdown, ImportReport};
+use chrono::Local;
+use serde::Serialize;
+use std::path::Path;
+
+pub fn import_day_one(db: &Database, path: &Path) -> Result<ImportReport, String> {
+    let json = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+
+    let mut report = ImportReport::default();
+    let entries = day_one::parse(&json, &mut report)?;
+
+    let mut conn = db.conn()?;
+    interop::save_entries(&mut conn, entries, &mut report)?;
+
+    Ok(report)
+}
+
+pub fn import_markdown_folder(db: &Database, path: &Path) -> Result<ImportReport, String> {
+    let mut report = ImportReport::default();
+    let entries = markdown::parse_folder(path, &mut report)?;
+
+    let mut conn = db.conn()?;
+    interop::save_entries(&mut conn, entries, &mut report)?;
+
+    Ok(report)
+}
+
+pub fn import_todo_txt(db: &Database, content: &str) -> Result<ImportReport, String> {
+    let mut conn = db.conn()?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    let mut report = ImportReport::default();
+    let today = Local::now().date_naive();
+
+    for (index, line) in content.lines().enumerate() {
+        let Some(item) = TodoItem::parse(line) else {
+            continue;
+        };
+        if item.text.is_empty() {
+            report.skip(format!("line {}", index + 1), "task has no description");
+            continue;
+        }
+
+        let input = CreateTaskInput {
+            title: item.task_title(),
+            description: item.description.clone(),
+            // Tasks always need a day; undated todo.txt items land on today
+            scheduled_date: Date(item.due.unwrap_or(today)),
+            scheduled_time: None,
+            duration_minutes: None,
+            priority: Some(item.task_priority()),
+            reminder_minutes: None,
+            color: None,
+            end_date: None,
+            is_all_day: None,
+            time_zone: None,
+        };
+
+        let tasks = TaskRepository::new(&tx);
+        let id = tasks.insert(&input, None).map_err(|e| e.to_string())?;
+        if let Some(created) = item.creation_date {
+            tasks
+                .set_created_at(&id, &format!("{} 00:00:00", created.format("%Y-%m-%d")))
+                .map_err(|e| e.to_string())?;
+        }
+        if item.completed {
+            let completed_at = item
+                .completion_date
+                .map(|done| format!("{}T00:00:00Z", done.format("%Y-%m-%d")));
+            tasks
+                .set_completed(&id, true, completed_at.as_deref())
+                .map_err(|e| e.to_string())?;
+        }
+        report.imported += 1;
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(report)
+}
+
+pub fn export_todo_txt(
+    db: &Database,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> Result<String, String> {
+    let conn = db.conn()?;
+
+    let tasks = TaskRepository::new(&conn)
+        .scheduled_between(start_date, end_date)
+        .map_err(|e| e.to_string())?;
+
+    let lines: Vec<String> = tasks
+        .iter()
+        .map(|task| TodoItem::from_task(task).to_line())
+        .collect();
+
+    Ok(lines.join("\n"))
+}
+
+#[derive(Debug, Serialize)]
+pub struct JournalExport {
+    pub tasks: Vec<Task>,
+    pub goals: Vec<DailyGoal>,
+    pub entries: Vec<JournalEntry>,
+}
+
+pub fn export_json(
+    db: &Database,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> Result<String, String> {
+    let conn = db.conn()?;
+
+    let export = JournalExport {
+        tasks: TaskRepository::new(&conn)
+            .scheduled_between(start_date, end_date)
+            .map_err(|e| e.to_string())?,
+        goals: GoalRepository::new(&conn)
+            .between(start_date, end_date)
+            .map_err(|e| e.to_string())?,
+        entries: JournalRepository::new(&conn)
+            .between(start_date, end_date)
+            .map_err(|e| e.to_string())?,
+    };
+    serde_json::to_string_pretty(&export).map_err(|e| e.to_string())
+}
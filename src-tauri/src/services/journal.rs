@@ -0,0 +1,381 @@
+use crate::database::{
+    CreateEntryInput, Database, GoalRepository, JournalEntry, JournalRepository, Mood,
+    MoodCorrelations, MoodCount, MoodDay, MoodReport, MoodWeekday, TaskRepository,
+    UpdateEntryInput, UpsertEntryInput, WellbeingRepository,
+};
+use crate::services::goals::WEEKDAYS;
+use chrono::{DateTime, Datelike, NaiveDate, SecondsFormat, Utc};
+use rusqlite::Connection;
+use std::collections::{BTreeMap, HashMap};
+
+/// The day's first entry, the one the day view edits.
+pub fn get_entry_for_date(db: &Database, date: &str) -> Result<Option<JournalEntry>, String> {
+    let conn = db.conn()?;
+
+    JournalRepository::new(&conn)
+        .for_date(date)
+        .map_err(|e| e.to_string())
+}
+
+pub fn upsert_entry(db: &Database, input: UpsertEntryInput) -> Result<JournalEntry, String> {
+    let conn = db.conn()?;
+    let journal = JournalRepository::new(&conn);
+    let entry_date = input.entry_date.to_string();
+
+    // Check if entry exists for this date
+    let existing = journal.for_date(&entry_date).map_err(|e| e.to_string())?;
+
+    let id = match existing {
+        Some(entry) => journal
+            .update(
+                &entry.id,
+                entry.title.as_deref(),
+                &input.content,
+                input.mood.map(Mood::as_str),
+            )
+            .map(|_| entry.id),
+        None => journal.insert(
+            &entry_date,
+            None,
+            &input.content,
+            input.mood.map(Mood::as_str),
+            Some(&now()),
+        ),
+    }
+    .map_err(|e| e.to_string())?;
+
+    journal.get(&id).map_err(|e| e.to_string())
+}
+
+fn now() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Every entry between the two dates, in the order they were written.
+pub fn list_entries(
+    db: &Database,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<JournalEntry>, String> {
+    if end_date < start_date {
+        return Err("End date can't be before the start date".to_string());
+    }
+    let conn = db.conn()?;
+
+    JournalRepository::new(&conn)
+        .between(Some(start_date), Some(end_date))
+        .map_err(|e| e.to_string())
+}
+
+pub fn create_entry(db: &Database, input: CreateEntryInput) -> Result<JournalEntry, String> {
+    let created_at = match input.created_at {
+        Some(ref value) => DateTime::parse_from_rfc3339(value)
+            .map(|time| {
+                time.with_timezone(&Utc)
+                    .to_rfc3339_opts(SecondsFormat::Secs, true)
+            })
+            .map_err(|_| format!("invalid timestamp \"{}\", expected RFC 3339", value))?,
+        None => now(),
+    };
+
+    let conn = db.conn()?;
+    let journal = JournalRepository::new(&conn);
+
+    let id = journal
+        .insert(
+            &input.entry_date.to_string(),
+            input.title.as_deref(),
+            &input.content,
+            input.mood.map(Mood::as_str),
+            Some(&created_at),
+        )
+        .map_err(|e| e.to_string())?;
+    journal.get(&id).map_err(|e| e.to_string())
+}
+
+pub fn update_entry(db: &Database, input: UpdateEntryInput) -> Result<JournalEntry, String> {
+    let conn = db.conn()?;
+    let journal = JournalRepository::new(&conn);
+
+    journal.get(&input.id).map_err(|e| e.to_string())?;
+    journal
+        .update(
+            &input.id,
+            input.title.as_deref(),
+            &input.content,
+            input.mood.map(Mood::as_str),
+        )
+        .map_err(|e| e.to_string())?;
+    journal.get(&input.id).map_err(|e| e.to_string())
+}
+
+pub fn delete_entry(db: &Database, id: &str) -> Result<(), String> {
+    let conn = db.conn()?;
+
+    JournalRepository::new(&conn)
+        .delete(id)
+        .map_err(|e| e.to_string())
+}
+
+/// Average of the values, or `None` if there aren't any.
+pub(crate) fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// Pearson correlation of the pairs, if there are enough of them to mean
+/// anything.
+fn correlation(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < 3 {
+        return None;
+    }
+    let mean_x = mean(pairs.iter().map(|pair| pair.0))?;
+    let mean_y = mean(pairs.iter().map(|pair| pair.1))?;
+
+    let (mut covariance, mut spread_x, mut spread_y) = (0.0, 0.0, 0.0);
+    for (x, y) in pairs {
+        covariance += (x - mean_x) * (y - mean_y);
+        spread_x += (x - mean_x).powi(2);
+        spread_y += (y - mean_y).powi(2);
+    }
+    (spread_x > 0.0 && spread_y > 0.0).then(|| covariance / (spread_x * spread_y).sqrt())
+}
+
+/// How mood was spread over the days between the two dates and how it lines
+/// up with the day's task completion, goal completion and tracked time.
+/// A day's mood is its journal entry's, or else the last mood checked in
+/// that day. Entries whose mood isn't one of the five known ones are left
+/// out.
+pub fn get_mood_report(
+    db: &Database,
+    start_date: &str,
+    end_date: &str,
+) -> Result<MoodReport, String> {
+    if end_date < start_date {
+        return Err("End date can't be before the start date".to_string());
+    }
+
+    let conn = db.conn()?;
+    mood_report(&conn, start_date, end_date)
+}
+
+fn mood_report(conn: &Connection, start_date: &str, end_date: &str) -> Result<MoodReport, String> {
+    // Later check-ins, then later journal entries, have the final say
+    let mut moods: BTreeMap<String, Mood> = BTreeMap::new();
+    for check_in in WellbeingRepository::new(conn)
+        .check_ins_between(start_date, end_date)
+        .map_err(|e| e.to_string())?
+    {
+        if let Some(mood) = check_in.mood {
+            moods.insert(check_in.entry_date, mood);
+        }
+    }
+    for entry in JournalRepository::new(conn)
+        .between(Some(start_date), Some(end_date))
+        .map_err(|e| e.to_string())?
+    {
+        if let Some(mood) = entry.mood.and_then(|mood| mood.parse().ok()) {
+            moods.insert(entry.entry_date, mood);
+        }
+    }
+
+    let tasks = TaskRepository::new(conn);
+    let task_rates: HashMap<String, f64> = tasks
+        .completion_rates(start_date, end_date)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .collect();
+    let tracked: HashMap<String, i32> = tasks
+        .tracked_minutes(start_date, end_date)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .collect();
+
+    let goal_rates: HashMap<String, f64> = GoalRepository::new(conn)
+        .daily_totals(start_date, end_date)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|(date, totals)| (date, totals.completion_rate))
+        .collect();
+
+    let series: Vec<MoodDay> = moods
+        .into_iter()
+        .map(|(date, mood)| MoodDay {
+            score: mood.score(),
+            task_completion_rate: task_rates.get(&date).copied(),
+            goal_completion_rate: goal_rates.get(&date).copied(),
+            tracked_minutes: tracked.get(&date).copied().unwrap_or(0),
+            mood,
+            date,
+        })
+        .collect();
+
+    let days_with_mood = series.len() as i32;
+    let distribution = Mood::ALL
+        .into_iter()
+        .map(|mood| {
+            let days: Vec<&MoodDay> = series.iter().filter(|day| day.mood == mood).collect();
+            MoodCount {
+                mood,
+                days: days.len() as i32,
+                share: if days_with_mood > 0 {
+                    days.len() as f64 / days_with_mood as f64
+                } else {
+                    0.0
+                },
+                task_completion_rate: mean(days.iter().filter_map(|day| day.task_completion_rate)),
+                goal_completion_rate: mean(days.iter().filter_map(|day| day.goal_completion_rate)),
+                tracked_minutes: mean(days.iter().map(|day| day.tracked_minutes as f64)),
+            }
+        })
+        .collect();
+
+    let weekday_of = |date: &str| {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map(|date| date.weekday().num_days_from_sunday().to_string())
+            .unwrap_or_default()
+    };
+    let weekdays = WEEKDAYS
+        .iter()
+        .map(|(number, name)| {
+            let scores: Vec<f64> = series
+                .iter()
+                .filter(|day| weekday_of(&day.date) == *number)
+                .map(|day| day.score)
+                .collect();
+            MoodWeekday {
+                weekday: name.to_string(),
+                days: scores.len() as i32,
+                average_score: mean(scores.into_iter()),
+            }
+        })
+        .collect();
+
+    let pairs = |measure: fn(&MoodDay) -> Option<f64>| -> Vec<(f64, f64)> {
+        series
+            .iter()
+            .filter_map(|day| Some((day.score, measure(day)?)))
+            .collect()
+    };
+    let correlations = MoodCorrelations {
+        task_completion: correlation(&pairs(|day| day.task_completion_rate)),
+        goal_completion: correlation(&pairs(|day| day.goal_completion_rate)),
+        tracked_minutes: correlation(&pairs(|day| Some(day.tracked_minutes as f64))),
+    };
+
+    Ok(MoodReport {
+        start_date: start_date.to_string(),
+        end_date: end_date.to_string(),
+        days_with_mood,
+        average_score: mean(series.iter().map(|day| day.score)),
+        distribution,
+        series,
+        weekdays,
+        correlations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::fixtures::connection;
+    use crate::database::WellbeingCheckInInput;
+    use rusqlite::params;
+
+    fn check_in(conn: &Connection, date: &str, recorded_at: &str, mood: Mood) {
+        let input = WellbeingCheckInInput {
+            entry_date: date.parse().unwrap(),
+            recorded_at: None,
+            mood: Some(mood),
+            energy: None,
+            stress: None,
+            note: None,
+        };
+        WellbeingRepository::new(conn)
+            .insert_check_in(&input, recorded_at)
+            .unwrap();
+    }
+
+    fn task(conn: &Connection, id: &str, date: &str, is_completed: bool) {
+        conn.execute(
+            "INSERT INTO tasks (id, title, scheduled_date, is_completed) VALUES (?, 'Task', ?, ?)",
+            params![id, date, is_completed],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn correlation_needs_spread_in_both_series() {
+        assert_eq!(correlation(&[(1.0, 2.0), (2.0, 4.0)]), None);
+        assert_eq!(correlation(&[(3.0, 1.0), (3.0, 2.0), (3.0, 5.0)]), None);
+        assert_eq!(correlation(&[(1.0, 0.5), (2.0, 0.5), (3.0, 0.5)]), None);
+
+        let rising = correlation(&[(1.0, 10.0), (2.0, 20.0), (3.0, 30.0)]).unwrap();
+        assert!((rising - 1.0).abs() < 1e-9);
+        let falling = correlation(&[(1.0, 3.0), (2.0, 2.0), (3.0, 1.0), (4.0, 0.0)]).unwrap();
+        assert!((falling + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn journal_moods_override_check_ins() {
+        let conn = connection();
+
+        // Only check-ins; the last one of the day counts
+        check_in(&conn, "2026-05-04", "2026-05-04T08:00:00Z", Mood::Bad);
+        check_in(&conn, "2026-05-04", "2026-05-04T20:00:00Z", Mood::Good);
+        // A check-in and an entry
+        check_in(&conn, "2026-05-05", "2026-05-05T21:00:00Z", Mood::Terrible);
+        JournalRepository::new(&conn)
+            .insert("2026-05-05", None, "Fine day", Some("great"), None)
+            .unwrap();
+        // Only an entry, with a mood the report doesn't know
+        JournalRepository::new(&conn)
+            .insert("2026-05-06", None, "Meh", Some("meh"), None)
+            .unwrap();
+        check_in(&conn, "2026-05-07", "2026-05-07T09:00:00Z", Mood::Okay);
+
+        let report = mood_report(&conn, "2026-05-01", "2026-05-31").unwrap();
+        let series: Vec<(&str, Mood)> = report
+            .series
+            .iter()
+            .map(|day| (day.date.as_str(), day.mood))
+            .collect();
+        assert_eq!(
+            series,
+            [
+                ("2026-05-04", Mood::Good),
+                ("2026-05-05", Mood::Great),
+                ("2026-05-07", Mood::Okay),
+            ]
+        );
+        assert_eq!(report.days_with_mood, 3);
+        assert_eq!(report.average_score, Some(4.0));
+
+        let days: Vec<i32> = report.distribution.iter().map(|count| count.days).collect();
+        assert_eq!(days, [1, 1, 1, 0, 0]);
+        assert_eq!(report.distribution[3].share, 0.0);
+        assert_eq!(report.distribution[3].tracked_minutes, None);
+
+        let monday = &report.weekdays[0];
+        assert_eq!((monday.weekday.as_str(), monday.days), ("Monday", 1));
+        assert_eq!(monday.average_score, Some(4.0));
+    }
+
+    #[test]
+    fn constant_measures_have_no_correlation() {
+        let conn = connection();
+        for (day, mood) in [("01", Mood::Bad), ("02", Mood::Okay), ("03", Mood::Great)] {
+            let date = format!("2026-05-{}", day);
+            check_in(&conn, &date, &format!("{}T09:00:00Z", date), mood);
+            task(&conn, &format!("done-{}", day), &date, true);
+            task(&conn, &format!("open-{}", day), &date, false);
+        }
+
+        let report = mood_report(&conn, "2026-05-01", "2026-05-03").unwrap();
+        assert_eq!(report.series[0].task_completion_rate, Some(0.5));
+        // Every day half done and nothing tracked: no spread to correlate
+        assert_eq!(report.correlations.task_completion, None);
+        assert_eq!(report.correlations.tracked_minutes, None);
+        assert_eq!(report.correlations.goal_completion, None);
+    }
+}
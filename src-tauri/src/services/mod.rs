@@ -0,0 +1,13 @@
+//! Command logic shared by the Tauri commands and the `journal-cli` binary.
+//! Nothing in here depends on `tauri`.
+
+pub mod dependencies;
+pub mod focus;
+pub mod goals;
+pub mod habits;
+pub mod interop;
+pub mod journal;
+pub mod scheduling;
+pub mod tasks;
+pub mod time_tracking;
+pub mod wellbeing;
@@ -0,0 +1,219 @@
+use crate::database::{
+    ApplyScheduleInput, AutoScheduleInput, Database, PlannedTask, SchedulePlan, Task, TaskConflict,
+    TaskRepository, Time, UnplacedTask,
+};
+use crate::scheduling::{self, zones, Candidate, Placement, TimedSlot};
+use crate::services::tasks;
+use chrono::{Duration, NaiveDate};
+use std::collections::HashMap;
+
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| format!("Invalid date: {}", value))
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Timed tasks that touch `start..=end`, including ones from earlier days
+/// that run past midnight into the range.
+fn slots_between(
+    db: &Database,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<TimedSlot>, String> {
+    let conn = db.conn()?;
+    let tasks = TaskRepository::new(&conn)
+        .overlapping(&format_date(start), &format_date(end))
+        .map_err(|e| e.to_string())?;
+    Ok(tasks.iter().filter_map(TimedSlot::from_task).collect())
+}
+
+/// Overlapping timed tasks whose overlap falls between the two dates.
+pub fn find_conflicts(
+    db: &Database,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<TaskConflict>, String> {
+    let start = parse_date(start_date)?;
+    let end = parse_date(end_date)?;
+
+    let range_start = start.format("%Y-%m-%d 00:00").to_string();
+    let range_end = (end + Duration::days(1))
+        .format("%Y-%m-%d 00:00")
+        .to_string();
+
+    Ok(scheduling::find_overlaps(slots_between(db, start, end)?)
+        .into_iter()
+        .filter(|c| c.overlap_end > range_start && c.overlap_start < range_end)
+        .collect())
+}
+
+/// Conflicts a task would have at the given slot, without saving anything.
+/// Meant to be cheap enough to call while a task is dragged in the calendar.
+pub fn find_slot_conflicts(
+    db: &Database,
+    task_id: Option<&str>,
+    scheduled_date: &str,
+    scheduled_time: &str,
+    duration_minutes: i32,
+) -> Result<Vec<TaskConflict>, String> {
+    let slot = TimedSlot::new(
+        task_id.unwrap_or(""),
+        scheduled_date,
+        scheduled_time,
+        duration_minutes,
+    )
+    .ok_or_else(|| format!("Invalid slot: {} {}", scheduled_date, scheduled_time))?;
+
+    let others = slots_between(db, slot.start.date(), slot.end.date())?;
+    Ok(scheduling::overlaps_with(&slot, &others))
+}
+
+/// Conflicts for a task as saved.
+pub fn conflicts_for_task(db: &Database, task: &Task) -> Result<Vec<TaskConflict>, String> {
+    match TimedSlot::from_task(task) {
+        Some(slot) => {
+            let others = slots_between(db, slot.start.date(), slot.end.date())?;
+            Ok(scheduling::overlaps_with(&slot, &others))
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
+fn minute_of(time: Time) -> i32 {
+    scheduling::minute_of_day(time.0)
+}
+
+/// Proposes times for the day's untimed tasks inside working hours, around
+/// the timed tasks and breaks already in place. Nothing is saved.
+pub fn auto_schedule_day(db: &Database, input: AutoScheduleInput) -> Result<SchedulePlan, String> {
+    let date = input.date.0;
+    let day_start = input.work_start.map_or(9 * 60, minute_of);
+    let day_end = input.work_end.map_or(17 * 60, minute_of);
+    if day_end <= day_start {
+        return Err("Working hours must end after they start".to_string());
+    }
+    let buffer_minutes = input.buffer_minutes.unwrap_or(5).max(0);
+
+    let breaks: Vec<(i32, i32)> = input
+        .breaks
+        .iter()
+        .map(|b| (minute_of(b.start), minute_of(b.end)))
+        .collect();
+
+    // Timed tasks, including ones from the day before that run past midnight
+    let midnight = date.and_hms_opt(0, 0, 0).ok_or("Invalid date")?;
+    let busy: Vec<(i32, i32)> = slots_between(db, date, date)?
+        .iter()
+        .map(|slot| {
+            (
+                (slot.start - midnight).num_minutes() as i32,
+                (slot.end - midnight).num_minutes() as i32,
+            )
+        })
+        .collect();
+
+    let day = input.date.to_string();
+    let day_tasks = tasks::get_tasks_for_date_range(db, &day, &day)?;
+    let untimed: HashMap<&str, &Task> = day_tasks
+        .iter()
+        .filter(|t| t.scheduled_time.is_none() && !t.is_completed)
+        // All-day and multi-day tasks aren't blocks of work to fit in
+        .filter(|t| !t.is_all_day && t.end_date.is_none())
+        .filter(|t| {
+            input
+                .task_ids
+                .as_ref()
+                .is_none_or(|ids| ids.iter().any(|id| id == &t.id))
+        })
+        .map(|t| (t.id.as_str(), t))
+        .collect();
+
+    let candidates = untimed
+        .values()
+        .map(|t| Candidate {
+            task_id: t.id.clone(),
+            duration_minutes: t.duration_minutes,
+            priority: t.priority.clone(),
+            created_at: t.created_at.clone(),
+        })
+        .collect();
+
+    let mut plan = SchedulePlan {
+        date: day.clone(),
+        planned: Vec::new(),
+        unplaced: Vec::new(),
+    };
+
+    for placement in scheduling::plan_day(
+        day_start,
+        day_end,
+        &busy,
+        &breaks,
+        buffer_minutes,
+        candidates,
+    ) {
+        match placement {
+            Placement::Placed {
+                task_id,
+                start_minute,
+            } => {
+                let task = untimed[task_id.as_str()];
+                plan.planned.push(PlannedTask {
+                    task_id,
+                    title: task.title.clone(),
+                    scheduled_time: scheduling::time_of_minute(start_minute)
+                        .map(Time)
+                        .ok_or("Invalid planned time")?,
+                    duration_minutes: task.duration_minutes,
+                    priority: task.priority.clone(),
+                });
+            }
+            Placement::Unplaced { task_id, reason } => {
+                let task = untimed[task_id.as_str()];
+                plan.unplaced.push(UnplacedTask {
+                    task_id,
+                    title: task.title.clone(),
+                    reason,
+                });
+            }
+        }
+    }
+
+    plan.planned.sort_by_key(|planned| planned.scheduled_time);
+    Ok(plan)
+}
+
+/// Saves a plan from `auto_schedule_day` in one transaction. Fails without
+/// changing anything if a task was given a time or moved in the meantime.
+pub fn apply_schedule(db: &Database, input: ApplyScheduleInput) -> Result<Vec<Task>, String> {
+    let mut conn = db.conn()?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    let zone_name = zones::current_zone().name();
+    let tasks = TaskRepository::new(&tx);
+    let mut applied = Vec::with_capacity(input.planned.len());
+    for planned in &input.planned {
+        let scheduled = tasks
+            .schedule_untimed(
+                &planned.task_id,
+                input.date,
+                planned.scheduled_time,
+                zone_name,
+            )
+            .map_err(|e| e.to_string())?;
+        if !scheduled {
+            return Err(format!(
+                "\"{}\" changed since the plan was made; plan again",
+                planned.title
+            ));
+        }
+
+        let task = tasks.get(&planned.task_id).map_err(|e| e.to_string())?;
+        applied.push(task);
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(applied)
+}
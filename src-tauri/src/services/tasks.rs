@@ -0,0 +1,311 @@
+use crate::database::{
+    CreateTaskInput, DailyGoal, Database, GoalRepository, Task, TaskRepository, UpdateTaskInput,
+};
+use crate::quick_add::{self, QuickAdd};
+use crate::scheduling::{zones, TimedSlot};
+use chrono::{Duration, NaiveDate, NaiveTime};
+use chrono_tz::Tz;
+use rusqlite::Connection;
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+}
+
+/// First and last day the task occupies as seen from `zone`: through
+/// `end_date` for multi-day tasks, and into the next day for timed tasks
+/// that run past midnight. A timed task can also land on another day than
+/// `scheduled_date` when it was planned in a different zone.
+pub fn task_days(task: &Task, zone: Tz) -> Option<(NaiveDate, NaiveDate)> {
+    let mut first = parse_date(&task.scheduled_date)?;
+    let mut last = task
+        .end_date
+        .as_deref()
+        .and_then(parse_date)
+        .unwrap_or(first);
+
+    if let Some(slot) = TimedSlot::in_zone(task, zone) {
+        // A task ending exactly at midnight doesn't spill into the next day
+        let last_minute = (slot.end - Duration::minutes(1)).max(slot.start);
+        first = slot.start.date();
+        last = last.max(last_minute.date());
+    }
+
+    Some((first, last.max(first)))
+}
+
+fn check_span(task: &Task) -> Result<(), String> {
+    if task.is_all_day && task.scheduled_time.is_some() {
+        return Err("All-day tasks can't have a scheduled time".to_string());
+    }
+    if let Some(end_date) = &task.end_date {
+        if parse_date(end_date).is_none() {
+            return Err(format!("Invalid end date: {}", end_date));
+        }
+        if *end_date < task.scheduled_date {
+            return Err("End date can't be before the scheduled date".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Tasks for each day between the two dates. A task spanning several days
+/// is listed once per day, with `occurrence_date` set to that day.
+pub fn get_tasks_for_date_range(
+    db: &Database,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<Task>, String> {
+    let start = parse_date(start_date).ok_or_else(|| format!("Invalid date: {}", start_date))?;
+    let end = parse_date(end_date).ok_or_else(|| format!("Invalid date: {}", end_date))?;
+
+    let conn = db.conn()?;
+    let overlapping = TaskRepository::new(&conn)
+        .overlapping(start_date, end_date)
+        .map_err(|e| e.to_string())?;
+
+    Ok(occurrences(overlapping, start, end, zones::current_zone()))
+}
+
+/// One copy of each task per day it occupies between `start` and `end`,
+/// seen from `zone`. All-day tasks come first on each day, and a timed task
+/// carried over from the day before sorts as if it started at midnight.
+fn occurrences(tasks: Vec<Task>, start: NaiveDate, end: NaiveDate, zone: Tz) -> Vec<Task> {
+    let mut keyed = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        let Some((first, last)) = task_days(&task, zone) else {
+            continue;
+        };
+        let slot = TimedSlot::in_zone(&task, zone);
+
+        let mut day = first.max(start);
+        while day <= last.min(end) {
+            let time = slot.as_ref().map(|slot| {
+                if slot.start.date() == day {
+                    slot.start.time()
+                } else {
+                    NaiveTime::MIN
+                }
+            });
+            let mut occurrence = task.clone();
+            occurrence.occurrence_date = day.format("%Y-%m-%d").to_string();
+            keyed.push(((day, !task.is_all_day, time), occurrence));
+            day += Duration::days(1);
+        }
+    }
+
+    keyed.sort_by_key(|(key, _)| *key);
+    keyed.into_iter().map(|(_, task)| task).collect()
+}
+
+pub fn create_task(db: &Database, input: CreateTaskInput) -> Result<Task, String> {
+    let time_zone = match (&input.time_zone, &input.scheduled_time) {
+        (Some(name), _) => Some(zones::parse_zone(name)?.name().to_string()),
+        (None, Some(_)) => Some(zones::current_zone().name().to_string()),
+        (None, None) => None,
+    };
+
+    let mut conn = db.conn()?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let tasks = TaskRepository::new(&tx);
+
+    let task = tasks
+        .insert(&input, time_zone.as_deref())
+        .and_then(|id| tasks.get(&id))
+        .map_err(|e| e.to_string())?;
+
+    check_span(&task)?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(task)
+}
+
+/// Brings the auto-completing goals in `goal_ids` in line with their linked
+/// tasks, returning the goals that completed or reopened.
+fn sync_goals(conn: &Connection, goal_ids: &[String]) -> Result<Vec<DailyGoal>, String> {
+    let goals = GoalRepository::new(conn);
+
+    let mut changed = Vec::new();
+    for id in goal_ids {
+        if goals.sync_with_tasks(id).map_err(|e| e.to_string())? {
+            changed.push(goals.get(id).map_err(|e| e.to_string())?);
+        }
+    }
+    Ok(changed)
+}
+
+fn linked_goal_ids(conn: &Connection, task_id: &str) -> Result<Vec<String>, String> {
+    GoalRepository::new(conn)
+        .linked_to_task(task_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Saves the changes and returns the task along with the linked goals that
+/// completed or reopened because of them.
+pub fn update_task(
+    db: &Database,
+    input: UpdateTaskInput,
+) -> Result<(Task, Vec<DailyGoal>), String> {
+    if let Some(ref time_zone) = input.time_zone {
+        zones::parse_zone(time_zone)?;
+    }
+
+    let mut conn = db.conn()?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let tasks = TaskRepository::new(&tx);
+
+    // A task given a time for the first time is pinned to where we are
+    let updated = tasks
+        .update(&input, zones::current_zone().name())
+        .map_err(|e| e.to_string())?;
+    if !updated {
+        return Err("No fields to update".to_string());
+    }
+
+    let task = tasks.get(&input.id).map_err(|e| e.to_string())?;
+    check_span(&task)?;
+    let goals = sync_goals(&tx, &linked_goal_ids(&tx, &task.id)?)?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok((task, goals))
+}
+
+/// Returns whether the task was there to delete, and the goals it was
+/// linked to that completed or reopened without it.
+pub fn delete_task(db: &Database, id: &str) -> Result<(bool, Vec<DailyGoal>), String> {
+    let mut conn = db.conn()?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    // Read before the links go with the task
+    let goal_ids = linked_goal_ids(&tx, id)?;
+    let deleted = TaskRepository::new(&tx)
+        .delete(id)
+        .map_err(|e| e.to_string())?;
+    let goals = sync_goals(&tx, &goal_ids)?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok((deleted, goals))
+}
+
+/// Returns the task along with the linked goals that completed or reopened
+/// with it.
+pub fn toggle_task_completion(db: &Database, id: &str) -> Result<(Task, Vec<DailyGoal>), String> {
+    let mut conn = db.conn()?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    let task = TaskRepository::new(&tx)
+        .toggle_completion(id)
+        .map_err(|e| e.to_string())?;
+    let goals = sync_goals(&tx, &linked_goal_ids(&tx, &task.id)?)?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok((task, goals))
+}
+
+/// Expands a (possibly shortened) task id into the full id, failing if the
+/// prefix matches no task or more than one.
+pub fn resolve_task_id(db: &Database, prefix: &str) -> Result<String, String> {
+    let conn = db.conn()?;
+
+    let ids = TaskRepository::new(&conn)
+        .ids_with_prefix(prefix)
+        .map_err(|e| e.to_string())?;
+
+    match ids.as_slice() {
+        [id] => Ok(id.clone()),
+        [] => Err(format!("No task with id {}", prefix)),
+        _ => Err(format!("Task id {} is ambiguous", prefix)),
+    }
+}
+
+/// Parses a quick-add line relative to `today` and creates the task.
+pub fn quick_add_task(
+    db: &Database,
+    text: &str,
+    today: NaiveDate,
+) -> Result<(Task, QuickAdd), String> {
+    let parsed = quick_add::parse(text, today);
+    if parsed.title.is_empty() {
+        return Err("Quick add needs a title".to_string());
+    }
+
+    let tags = parsed
+        .tags
+        .iter()
+        .map(|tag| format!("#{}", tag))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let task = create_task(
+        db,
+        CreateTaskInput {
+            title: parsed.title.clone(),
+            description: (!tags.is_empty()).then_some(tags),
+            scheduled_date: parsed.scheduled_date.parse()?,
+            scheduled_time: parsed
+                .scheduled_time
+                .as_deref()
+                .map(str::parse)
+                .transpose()?,
+            duration_minutes: parsed.duration_minutes,
+            priority: parsed.priority.as_deref().map(str::parse).transpose()?,
+            reminder_minutes: parsed.reminder_minutes,
+            color: None,
+            end_date: None,
+            is_all_day: None,
+            time_zone: None,
+        },
+    )?;
+
+    Ok((task, parsed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::fixtures::{goal_input, no_changes, task_input};
+
+    fn task(db: &Database, title: &str) -> Task {
+        create_task(db, task_input(title, "2026-05-01")).unwrap()
+    }
+
+    /// An auto-completing goal linked to the tasks.
+    fn goal(db: &Database, task_ids: &[&str]) -> String {
+        let conn = db.conn().unwrap();
+        let goals = GoalRepository::new(&conn);
+        let id = goals.insert(&goal_input("2026-05-01", 1, "Ship")).unwrap();
+        goals.set_auto_complete(&id, true).unwrap();
+        for task_id in task_ids {
+            goals.link_task(&id, task_id).unwrap();
+        }
+        id
+    }
+
+    #[test]
+    fn linked_goals_follow_task_changes() {
+        let db = Database::in_memory().unwrap();
+        let first = task(&db, "First");
+        let second = task(&db, "Second");
+        let goal_id = goal(&db, &[&first.id, &second.id]);
+
+        let (_, synced) = toggle_task_completion(&db, &first.id).unwrap();
+        assert!(synced.is_empty());
+
+        let (_, synced) = toggle_task_completion(&db, &second.id).unwrap();
+        assert_eq!(synced.len(), 1);
+        assert_eq!(synced[0].id, goal_id);
+        assert!(synced[0].is_completed);
+
+        let reopen = UpdateTaskInput {
+            is_completed: Some(false),
+            ..no_changes(&second.id)
+        };
+        let (_, synced) = update_task(&db, reopen).unwrap();
+        assert!(!synced[0].is_completed);
+
+        // With the open task gone, the rest are done
+        let (deleted, synced) = delete_task(&db, &second.id).unwrap();
+        assert!(deleted);
+        assert!(synced[0].is_completed);
+
+        let (deleted, synced) = delete_task(&db, &second.id).unwrap();
+        assert!(!deleted && synced.is_empty());
+    }
+}
@@ -0,0 +1,337 @@
+use crate::database::{Database, TaskTime, TimeEntry, TimeReportPeriod};
+use chrono::{Datelike, Duration, NaiveDate, SecondsFormat, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+const TIME_ENTRY_COLUMNS: &str = "id, task_id, started_at, ended_at,
+     strftime('%s', COALESCE(ended_at, 'now')) - strftime('%s', started_at)";
+
+fn time_entry_from_row(row: &Row) -> rusqlite::Result<TimeEntry> {
+    Ok(TimeEntry {
+        id: row.get(0)?,
+        task_id: row.get(1)?,
+        started_at: row.get(2)?,
+        ended_at: row.get(3)?,
+        duration_seconds: row.get(4)?,
+    })
+}
+
+/// Timestamps are stored as UTC RFC 3339 so SQLite can do arithmetic on them.
+fn now() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Starts a timer on the task, stopping whichever timer was running.
+pub fn start_timer(db: &Database, task_id: &str) -> Result<TimeEntry, String> {
+    let conn = db.conn()?;
+    start(&conn, task_id)
+}
+
+fn start(conn: &Connection, task_id: &str) -> Result<TimeEntry, String> {
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+
+    let exists: bool = tx
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM tasks WHERE id = ?)",
+            params![task_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if !exists {
+        return Err(format!("No task with id {}", task_id));
+    }
+
+    let now = now();
+    tx.execute(
+        "UPDATE time_entries SET ended_at = ? WHERE ended_at IS NULL",
+        params![now],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let id = Uuid::new_v4().to_string();
+    tx.execute(
+        "INSERT INTO time_entries (id, task_id, started_at) VALUES (?, ?, ?)",
+        params![id, task_id, now],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let entry = tx
+        .query_row(
+            &format!(
+                "SELECT {} FROM time_entries WHERE id = ?",
+                TIME_ENTRY_COLUMNS
+            ),
+            params![id],
+            time_entry_from_row,
+        )
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(entry)
+}
+
+/// Stops the running timer, if any, and returns the finished entry.
+pub fn stop_timer(db: &Database) -> Result<Option<TimeEntry>, String> {
+    let conn = db.conn()?;
+    stop(&conn)
+}
+
+fn stop(conn: &Connection) -> Result<Option<TimeEntry>, String> {
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+
+    let running: Option<String> = tx
+        .query_row(
+            "SELECT id FROM time_entries WHERE ended_at IS NULL",
+            [],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    let Some(id) = running else {
+        return Ok(None);
+    };
+
+    tx.execute(
+        "UPDATE time_entries SET ended_at = ? WHERE id = ?",
+        params![now(), id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let entry = tx
+        .query_row(
+            &format!(
+                "SELECT {} FROM time_entries WHERE id = ?",
+                TIME_ENTRY_COLUMNS
+            ),
+            params![id],
+            time_entry_from_row,
+        )
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(Some(entry))
+}
+
+/// The running timer, so the UI can pick it back up after a restart.
+pub fn get_running_timer(db: &Database) -> Result<Option<TimeEntry>, String> {
+    let conn = db.conn()?;
+
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM time_entries WHERE ended_at IS NULL",
+            TIME_ENTRY_COLUMNS
+        ),
+        [],
+        time_entry_from_row,
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+pub fn get_time_entries(db: &Database, task_id: &str) -> Result<Vec<TimeEntry>, String> {
+    let conn = db.conn()?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM time_entries WHERE task_id = ? ORDER BY started_at",
+            TIME_ENTRY_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let entries = stmt
+        .query_map(params![task_id], time_entry_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(entries)
+}
+
+/// Compares planned `duration_minutes` with tracked time for the tasks
+/// scheduled between the two dates, grouped by day or by week (starting
+/// Monday). Tracked time counts towards the day the task is scheduled on.
+pub fn get_time_report(
+    db: &Database,
+    start_date: &str,
+    end_date: &str,
+    by_week: bool,
+) -> Result<Vec<TimeReportPeriod>, String> {
+    let conn = db.conn()?;
+    time_report(&conn, start_date, end_date, by_week)
+}
+
+fn time_report(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    by_week: bool,
+) -> Result<Vec<TimeReportPeriod>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.scheduled_date, t.id, t.title, t.duration_minutes,
+                    COALESCE(SUM(strftime('%s', COALESCE(e.ended_at, 'now'))
+                                 - strftime('%s', e.started_at)), 0) / 60
+             FROM tasks t
+             LEFT JOIN time_entries e ON e.task_id = t.id
+             WHERE t.scheduled_date >= ? AND t.scheduled_date <= ?
+             GROUP BY t.id
+             ORDER BY t.scheduled_date, t.scheduled_time",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(params![start_date, end_date], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                TaskTime {
+                    task_id: row.get(1)?,
+                    title: row.get(2)?,
+                    estimated_minutes: row.get(3)?,
+                    actual_minutes: row.get(4)?,
+                },
+            ))
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut periods: BTreeMap<String, TimeReportPeriod> = BTreeMap::new();
+    for (scheduled_date, task) in rows {
+        let period_start = match NaiveDate::parse_from_str(&scheduled_date, "%Y-%m-%d") {
+            Ok(date) if by_week => {
+                let monday = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+                monday.format("%Y-%m-%d").to_string()
+            }
+            _ => scheduled_date,
+        };
+
+        let period = periods
+            .entry(period_start.clone())
+            .or_insert_with(|| TimeReportPeriod {
+                period_start,
+                estimated_minutes: 0,
+                actual_minutes: 0,
+                tasks: Vec::new(),
+            });
+        period.estimated_minutes += task.estimated_minutes;
+        period.actual_minutes += task.actual_minutes;
+        period.tasks.push(task);
+    }
+
+    Ok(periods.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::fixtures::connection;
+
+    fn task(conn: &Connection, id: &str, date: &str, duration_minutes: i32) {
+        conn.execute(
+            "INSERT INTO tasks (id, title, scheduled_date, duration_minutes)
+             VALUES (?, 'Task', ?, ?)",
+            params![id, date, duration_minutes],
+        )
+        .unwrap();
+    }
+
+    fn entry(conn: &Connection, task_id: &str, started_at: &str, ended_at: &str) {
+        conn.execute(
+            "INSERT INTO time_entries (id, task_id, started_at, ended_at) VALUES (?, ?, ?, ?)",
+            params![Uuid::new_v4().to_string(), task_id, started_at, ended_at],
+        )
+        .unwrap();
+    }
+
+    fn running(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn
+            .prepare("SELECT task_id FROM time_entries WHERE ended_at IS NULL")
+            .unwrap();
+        stmt.query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn starting_a_timer_stops_the_running_one() {
+        let conn = connection();
+        task(&conn, "a", "2026-03-02", 30);
+        task(&conn, "b", "2026-03-02", 30);
+
+        let first = start(&conn, "a").unwrap();
+        let second = start(&conn, "b").unwrap();
+        assert_eq!(running(&conn), ["b"]);
+
+        let first: Option<String> = conn
+            .query_row(
+                "SELECT ended_at FROM time_entries WHERE id = ?",
+                params![first.id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(first.as_deref(), Some(second.started_at.as_str()));
+    }
+
+    #[test]
+    fn stopping_finishes_the_running_entry_once() {
+        let conn = connection();
+        task(&conn, "a", "2026-03-02", 30);
+
+        let started = start(&conn, "a").unwrap();
+        let stopped = stop(&conn).unwrap().unwrap();
+        assert_eq!(stopped.id, started.id);
+        assert!(stopped.ended_at.is_some());
+        assert!(running(&conn).is_empty());
+
+        assert!(stop(&conn).unwrap().is_none());
+    }
+
+    #[test]
+    fn timer_needs_an_existing_task() {
+        let conn = connection();
+        assert!(start(&conn, "missing").is_err());
+        assert!(running(&conn).is_empty());
+    }
+
+    #[test]
+    fn only_one_timer_can_run() {
+        let conn = connection();
+        task(&conn, "a", "2026-03-02", 30);
+        start(&conn, "a").unwrap();
+
+        let second = conn.execute(
+            "INSERT INTO time_entries (id, task_id, started_at) VALUES ('x', 'a', '2026-03-02T09:00:00Z')",
+            [],
+        );
+        assert!(second.is_err());
+    }
+
+    #[test]
+    fn report_groups_by_day_or_by_week_from_monday() {
+        let conn = connection();
+        // Sunday, then Monday and Wednesday of the following week
+        task(&conn, "sun", "2026-03-01", 30);
+        task(&conn, "mon", "2026-03-02", 60);
+        task(&conn, "wed", "2026-03-04", 15);
+        entry(&conn, "mon", "2026-03-02T09:00:00Z", "2026-03-02T09:45:00Z");
+        entry(&conn, "mon", "2026-03-02T13:00:00Z", "2026-03-02T13:30:00Z");
+        entry(&conn, "wed", "2026-03-04T10:00:00Z", "2026-03-04T10:20:00Z");
+
+        let days = time_report(&conn, "2026-03-01", "2026-03-04", false).unwrap();
+        let starts: Vec<_> = days.iter().map(|p| p.period_start.as_str()).collect();
+        assert_eq!(starts, ["2026-03-01", "2026-03-02", "2026-03-04"]);
+        assert_eq!(days[0].actual_minutes, 0);
+        assert_eq!(days[1].estimated_minutes, 60);
+        assert_eq!(days[1].actual_minutes, 75);
+
+        let weeks = time_report(&conn, "2026-03-01", "2026-03-04", true).unwrap();
+        let starts: Vec<_> = weeks.iter().map(|p| p.period_start.as_str()).collect();
+        assert_eq!(starts, ["2026-02-23", "2026-03-02"]);
+        assert_eq!(weeks[1].estimated_minutes, 75);
+        assert_eq!(weeks[1].actual_minutes, 95);
+        assert_eq!(weeks[1].tasks.len(), 2);
+    }
+}
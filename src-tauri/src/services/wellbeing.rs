@@ -0,0 +1,246 @@
+use crate::database::{
+    Database, MetricKind, WellbeingCheckIn, WellbeingCheckInInput, WellbeingDay, WellbeingMetric,
+    WellbeingMetricInput, WellbeingRepository,
+};
+use crate::services::journal::mean;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::collections::BTreeMap;
+
+/// The check-in's time as stored: RFC 3339 in UTC, now if left out.
+fn recorded_at(input: &WellbeingCheckInInput) -> Result<String, String> {
+    let time = match input.recorded_at {
+        Some(ref value) => DateTime::parse_from_rfc3339(value)
+            .map(|time| time.with_timezone(&Utc))
+            .map_err(|_| format!("invalid timestamp \"{}\", expected RFC 3339", value))?,
+        None => Utc::now(),
+    };
+    Ok(time.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+fn validate_check_in(input: &WellbeingCheckInInput) -> Result<(), String> {
+    for (name, score) in [("Energy", input.energy), ("Stress", input.stress)] {
+        if score.is_some_and(|score| !(1..=5).contains(&score)) {
+            return Err(format!("{} must be between 1 and 5", name));
+        }
+    }
+    let empty_note = input
+        .note
+        .as_deref()
+        .is_none_or(|note| note.trim().is_empty());
+    if input.mood.is_none() && input.energy.is_none() && input.stress.is_none() && empty_note {
+        return Err("A check-in needs a mood, a score or a note".to_string());
+    }
+    Ok(())
+}
+
+pub fn get_wellbeing_check_in(db: &Database, id: &str) -> Result<WellbeingCheckIn, String> {
+    let conn = db.conn()?;
+
+    WellbeingRepository::new(&conn)
+        .check_in(id)
+        .map_err(|e| e.to_string())
+}
+
+pub fn add_wellbeing_check_in(
+    db: &Database,
+    input: WellbeingCheckInInput,
+) -> Result<WellbeingCheckIn, String> {
+    validate_check_in(&input)?;
+    let recorded_at = recorded_at(&input)?;
+    let conn = db.conn()?;
+    let wellbeing = WellbeingRepository::new(&conn);
+
+    let id = wellbeing
+        .insert_check_in(&input, &recorded_at)
+        .map_err(|e| e.to_string())?;
+    wellbeing.check_in(&id).map_err(|e| e.to_string())
+}
+
+pub fn update_wellbeing_check_in(
+    db: &Database,
+    id: &str,
+    input: WellbeingCheckInInput,
+) -> Result<WellbeingCheckIn, String> {
+    validate_check_in(&input)?;
+    let conn = db.conn()?;
+    let wellbeing = WellbeingRepository::new(&conn);
+    let existing = wellbeing.check_in(id).map_err(|e| e.to_string())?;
+
+    // Leaving the time out keeps the one already recorded
+    let recorded_at = match input.recorded_at {
+        Some(_) => recorded_at(&input)?,
+        None => existing.recorded_at,
+    };
+    wellbeing
+        .update_check_in(id, &input, &recorded_at)
+        .map_err(|e| e.to_string())?;
+    wellbeing.check_in(id).map_err(|e| e.to_string())
+}
+
+/// Deletes the check-in, returning it so callers know which day changed.
+pub fn delete_wellbeing_check_in(db: &Database, id: &str) -> Result<WellbeingCheckIn, String> {
+    let conn = db.conn()?;
+    let wellbeing = WellbeingRepository::new(&conn);
+
+    let check_in = wellbeing.check_in(id).map_err(|e| e.to_string())?;
+    wellbeing.delete_check_in(id).map_err(|e| e.to_string())?;
+    Ok(check_in)
+}
+
+pub fn set_sleep_hours(db: &Database, date: &str, hours: Option<f64>) -> Result<(), String> {
+    if hours.is_some_and(|hours| !(0.0..=24.0).contains(&hours)) {
+        return Err("Sleep must be between 0 and 24 hours".to_string());
+    }
+    let conn = db.conn()?;
+
+    WellbeingRepository::new(&conn)
+        .set_sleep(date, hours)
+        .map_err(|e| e.to_string())
+}
+
+pub fn get_wellbeing_metrics(db: &Database) -> Result<Vec<WellbeingMetric>, String> {
+    let conn = db.conn()?;
+
+    WellbeingRepository::new(&conn)
+        .metrics()
+        .map_err(|e| e.to_string())
+}
+
+fn validate_metric(input: &WellbeingMetricInput) -> Result<(), String> {
+    if input.name.trim().is_empty() {
+        return Err("Metric name can't be empty".to_string());
+    }
+    Ok(())
+}
+
+pub fn create_wellbeing_metric(
+    db: &Database,
+    input: WellbeingMetricInput,
+) -> Result<WellbeingMetric, String> {
+    validate_metric(&input)?;
+    let conn = db.conn()?;
+    let wellbeing = WellbeingRepository::new(&conn);
+
+    let id = wellbeing.insert_metric(&input).map_err(|e| e.to_string())?;
+    wellbeing.metric(&id).map_err(|e| e.to_string())
+}
+
+pub fn update_wellbeing_metric(
+    db: &Database,
+    id: &str,
+    input: WellbeingMetricInput,
+) -> Result<WellbeingMetric, String> {
+    validate_metric(&input)?;
+    let conn = db.conn()?;
+    let wellbeing = WellbeingRepository::new(&conn);
+
+    wellbeing.metric(id).map_err(|e| e.to_string())?;
+    wellbeing
+        .update_metric(id, &input)
+        .map_err(|e| e.to_string())?;
+    wellbeing.metric(id).map_err(|e| e.to_string())
+}
+
+/// Deletes the metric along with every value recorded for it.
+pub fn delete_wellbeing_metric(db: &Database, id: &str) -> Result<(), String> {
+    let conn = db.conn()?;
+
+    WellbeingRepository::new(&conn)
+        .delete_metric(id)
+        .map_err(|e| e.to_string())
+}
+
+/// Sets a metric for the day, or clears it with `None`. Boolean metrics
+/// take 1 for yes and 0 for no.
+pub fn set_metric_value(
+    db: &Database,
+    metric_id: &str,
+    date: &str,
+    value: Option<f64>,
+) -> Result<(), String> {
+    let conn = db.conn()?;
+    let wellbeing = WellbeingRepository::new(&conn);
+    let metric = wellbeing.metric(metric_id).map_err(|e| e.to_string())?;
+
+    match value {
+        Some(value) if !value.is_finite() => {
+            return Err(format!("{} needs a number", metric.name));
+        }
+        Some(value) if metric.kind == MetricKind::Boolean && value != 0.0 && value != 1.0 => {
+            return Err(format!("{} is yes or no, so 1 or 0", metric.name));
+        }
+        _ => {}
+    }
+
+    wellbeing
+        .set_metric_value(metric_id, date, value)
+        .map_err(|e| e.to_string())
+}
+
+fn day<'a>(days: &'a mut BTreeMap<String, WellbeingDay>, date: &str) -> &'a mut WellbeingDay {
+    days.entry(date.to_string())
+        .or_insert_with(|| WellbeingDay {
+            date: date.to_string(),
+            ..Default::default()
+        })
+}
+
+/// Every day between the two dates with anything recorded, earliest first.
+pub fn get_wellbeing(
+    db: &Database,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<WellbeingDay>, String> {
+    if end_date < start_date {
+        return Err("End date can't be before the start date".to_string());
+    }
+
+    let conn = db.conn()?;
+    let wellbeing = WellbeingRepository::new(&conn);
+
+    let mut days: BTreeMap<String, WellbeingDay> = BTreeMap::new();
+
+    for check_in in wellbeing
+        .check_ins_between(start_date, end_date)
+        .map_err(|e| e.to_string())?
+    {
+        day(&mut days, &check_in.entry_date.clone())
+            .check_ins
+            .push(check_in);
+    }
+    for (date, hours) in wellbeing
+        .sleep_between(start_date, end_date)
+        .map_err(|e| e.to_string())?
+    {
+        day(&mut days, &date).sleep_hours = Some(hours);
+    }
+    for value in wellbeing
+        .metric_values_between(start_date, end_date, None)
+        .map_err(|e| e.to_string())?
+    {
+        day(&mut days, &value.entry_date.clone())
+            .metrics
+            .push(value);
+    }
+
+    Ok(days
+        .into_values()
+        .map(|mut day| {
+            let check_ins = &day.check_ins;
+            day.average_mood = mean(check_ins.iter().filter_map(|c| c.mood.map(|m| m.score())));
+            day.average_energy = mean(check_ins.iter().filter_map(|c| c.energy.map(f64::from)));
+            day.average_stress = mean(check_ins.iter().filter_map(|c| c.stress.map(f64::from)));
+            day
+        })
+        .collect())
+}
+
+/// The day's wellbeing, empty if nothing was recorded.
+pub fn get_wellbeing_day(db: &Database, date: &str) -> Result<WellbeingDay, String> {
+    Ok(get_wellbeing(db, date, date)?
+        .pop()
+        .unwrap_or_else(|| WellbeingDay {
+            date: date.to_string(),
+            ..Default::default()
+        }))
+}
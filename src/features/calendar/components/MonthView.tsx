@@ -36,7 +36,7 @@ export function MonthView() {
   }
 
   const getTasksForDay = (day: Date): Task[] => {
-    return tasks.filter((task) => task.scheduled_date === format(day, 'yyyy-MM-dd'));
+    return tasks.filter((task) => task.occurrence_date === format(day, 'yyyy-MM-dd'));
   };
 
   const handleDayClick = (day: Date) => {
@@ -107,7 +107,7 @@ export function MonthView() {
                     <div className="space-y-0.5">
                       {dayTasks.slice(0, 3).map((task) => (
                         <MonthTaskDot
-                          key={task.id}
+                          key={`${task.id}:${task.occurrence_date}`}
                           task={task}
                           onClick={(e) => {
                             e.stopPropagation();
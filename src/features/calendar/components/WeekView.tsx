@@ -24,7 +24,7 @@ export function WeekView() {
 
   const getTasksForDay = (day: Date) => {
     const dayStr = format(day, 'yyyy-MM-dd');
-    return tasks.filter((task) => task.scheduled_date === dayStr && task.scheduled_time);
+    return tasks.filter((task) => task.occurrence_date === dayStr && task.scheduled_time);
   };
 
   const handleDayClick = (day: Date, hour?: number, minutes?: number) => {
@@ -156,7 +156,7 @@ function WeekDayColumn({ day, dateStr, tasks, onClick, onTaskClick }: WeekDayCol
       <div className="absolute inset-0 pointer-events-none">
         {tasks.map((task) => (
           <PositionedWeekTask
-            key={task.id}
+            key={`${task.id}:${task.occurrence_date}`}
             task={task}
             onClick={() => onTaskClick(task)}
           />
@@ -211,7 +211,7 @@ interface PositionedWeekTaskProps {
 
 function PositionedWeekTask({ task, onClick }: PositionedWeekTaskProps) {
   const { attributes, listeners, setNodeRef, isDragging } = useDraggable({
-    id: `week-task-${task.id}`,
+    id: `week-task-${task.id}:${task.occurrence_date}`,
     data: {
       type: 'task',
       task,
@@ -294,7 +294,7 @@ interface WeekResizeHandleProps {
 
 function WeekResizeHandle({ task }: WeekResizeHandleProps) {
   const { attributes, listeners, setNodeRef, isDragging } = useDraggable({
-    id: `week-resize-${task.id}`,
+    id: `week-resize-${task.id}:${task.occurrence_date}`,
     data: {
       type: 'resize',
       task,
@@ -10,6 +10,8 @@ export interface Task {
   reminder_minutes?: number;
   color?: string;
   created_at: string;
+  // The day this copy is shown on; a multi-day task comes back once per day
+  occurrence_date: string;
 }
 
 export interface CreateTaskInput {